@@ -1,6 +1,11 @@
-use std::{fs, process::Command};
-use color_eyre::{Result};
-use crate::kubectl::{self, FoundPod, KubectlRunner};
+use std::{fs, io::{self, Write}, path::Path, process::Command, thread, time::{Duration, Instant}};
+use color_eyre::{Result, eyre::{eyre, Context}};
+use crate::kubectl::{self, BackgroundCommand, FoundPod, KubectlRunner, Transfer};
+
+pub mod output;
+
+/// How often a copy in progress is measured. Measuring an upload runs `du` in the pod, so not too often.
+const COPY_PROGRESS_RATE: Duration = Duration::from_secs(1);
 
 pub fn open_in_vim(runner: &dyn KubectlRunner, pod: &FoundPod) -> Result<()> {
     let logs = kubectl::get_pod_logs(runner, pod, false, false).unwrap();
@@ -19,4 +24,197 @@ pub fn open_in_vim(runner: &dyn KubectlRunner, pod: &FoundPod) -> Result<()> {
     Ok(())
 }
 
+/// Works out which way to copy from `qk cp` arguments, one of which is in the pod as `<matcher>:/path`.
+///
+/// # Arguments
+/// * `source` - Where to copy from, e.g. `api:/tmp/heap.hprof` or `./app.yaml`.
+/// * `destination` - Where to copy to.
+///
+/// # Returns
+/// The matcher for the pod, and the transfer.
+///
+/// # Errors
+/// Returns an error unless exactly one of the arguments is in the pod.
+pub fn parse_transfer(source: &str, destination: &str) -> Result<(String, Transfer)> {
+    // A colon in a local path is fine as long as there's a slash before it, e.g. `./a:b`.
+    let in_pod = |arg: &str| arg.split_once(':')
+        .filter(|(matcher, path)| !matcher.is_empty() && !matcher.contains('/') && !path.is_empty())
+        .map(|(matcher, path)| (matcher.to_string(), path.to_string()));
+
+    match (in_pod(source), in_pod(destination)) {
+        (Some((matcher, remote)), None) => Ok((matcher, Transfer::Download { remote, local: destination.to_string() })),
+        (None, Some((matcher, remote))) => Ok((matcher, Transfer::Upload { local: source.to_string(), remote })),
+        _ => Err(eyre!("Exactly one of the source and destination must be in a pod, as <matcher>:/path")),
+    }
+}
+
+/// A `kubectl cp` running in the background, measured as it goes.
+pub struct CopyJob {
+    pub pod: FoundPod,
+    pub container: Option<String>,
+    pub transfer: Transfer,
+    /// How much there is to copy in bytes, if it could be measured up front.
+    pub total: Option<u64>,
+    /// How much has been copied in bytes, as of the last measurement.
+    pub copied: u64,
+    /// How much was at the destination before copying, e.g. other files in a directory being copied into,
+    /// so it isn't counted as copied.
+    already_there: u64,
+    /// How often the copy is measured while it's going, `COPY_PROGRESS_RATE` unless changed.
+    pub progress_rate: Duration,
+    command: Box<dyn BackgroundCommand>,
+    last_measured: Instant,
+}
+
+impl CopyJob {
+    /// Measures what's to be copied, then starts copying it.
+    ///
+    /// # Arguments
+    /// * `pod` - The pod to copy to or from.
+    /// * `container` - The container to copy to or from, or `None` for the pod's default container.
+    /// * `transfer` - What to copy, and which way.
+    ///
+    /// # Errors
+    /// Returns an error if a file to upload doesn't exist, or `kubectl` can't be started.
+    pub fn start(runner: &dyn KubectlRunner, pod: &FoundPod, container: Option<&str>, transfer: Transfer) -> Result<Self> {
+        let (total, already_there) = match &transfer {
+            Transfer::Download { remote, local } => (
+                kubectl::remote_size(runner, pod, container, remote).ok(),
+                local_size(Path::new(local)),
+            ),
+            Transfer::Upload { local, remote } => {
+                if !Path::new(local).exists() {
+                    return Err(eyre!("No such file to upload: {local}"));
+                }
+                (
+                    Some(local_size(Path::new(local))),
+                    kubectl::remote_size(runner, pod, container, remote).unwrap_or_default(),
+                )
+            }
+        };
+
+        let args = kubectl::cp_args(pod, container, &transfer);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let command = runner.spawn_background(&args).wrap_err("Could not run kubectl cp")?;
+
+        Ok(CopyJob {
+            pod: pod.clone(),
+            container: container.map(str::to_string),
+            transfer,
+            total,
+            copied: 0,
+            already_there,
+            progress_rate: COPY_PROGRESS_RATE,
+            command,
+            last_measured: Instant::now(),
+        })
+    }
+
+    /// Checks on the copy, measuring how far it has got every so often.
+    ///
+    /// # Returns
+    /// * `None` - While the copy is still going.
+    /// * `Some(Ok(()))` - Once the copy has finished.
+    /// * `Some(Err(_))` - If the copy failed, with what `kubectl cp` had to say about it.
+    pub fn poll(&mut self, runner: &dyn KubectlRunner) -> Option<Result<()>> {
+        match self.command.try_finish() {
+            Some(Ok(())) => {
+                self.copied = self.total.unwrap_or(self.copied);
+                Some(Ok(()))
+            }
+            Some(Err(err)) => Some(Err(eyre!("kubectl cp failed: {err}"))),
+            None => {
+                if self.last_measured.elapsed() >= self.progress_rate {
+                    self.last_measured = Instant::now();
+                    let size = match &self.transfer {
+                        Transfer::Download { local, .. } => Some(local_size(Path::new(local))),
+                        Transfer::Upload { remote, .. } => kubectl::remote_size(runner, &self.pod, self.container.as_deref(), remote).ok(),
+                    };
+                    if let Some(size) = size {
+                        self.copied = size.saturating_sub(self.already_there);
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    /// What's being copied and how far along it is, e.g. `api:/tmp/heap.hprof -> ./heap.hprof 12.0 MiB of 40.0 MiB (30%)`.
+    pub fn progress(&self) -> String {
+        let (source, destination) = match &self.transfer {
+            Transfer::Download { remote, local } => (format!("{}:{remote}", self.pod.name), local.clone()),
+            Transfer::Upload { local, remote } => (local.clone(), format!("{}:{remote}", self.pod.name)),
+        };
+
+        format!("{source} -> {destination} {}", format_progress(self.copied, self.total))
+    }
+}
+
+/// Copies files to or from a pod, printing progress as it goes.
+///
+/// # Arguments
+/// * `pod` - The pod to copy to or from.
+/// * `container` - The container to copy to or from, or `None` for the pod's default container.
+/// * `transfer` - What to copy, and which way.
+///
+/// # Errors
+/// Returns an error if the copy couldn't be started or failed.
+pub fn copy(runner: &dyn KubectlRunner, pod: &FoundPod, container: Option<&str>, transfer: Transfer) -> Result<()> {
+    let mut job = CopyJob::start(runner, pod, container, transfer)?;
+
+    loop {
+        let result = job.poll(runner);
+        // Padded to clear what's left of a longer line before it.
+        eprint!("\r{:<80}", job.progress());
+        io::stderr().flush()?;
+
+        if let Some(result) = result {
+            eprintln!();
+            return result;
+        }
+        thread::sleep(Duration::from_millis(250));
+    }
+}
+
+/// The size of a file, or of everything in a directory. Anything that can't be read counts as empty.
+fn local_size(path: &Path) -> u64 {
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|entry| local_size(&entry.path())).sum())
+            .unwrap_or_default(),
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
+}
+
+pub fn format_progress(copied: u64, total: Option<u64>) -> String {
+    match total {
+        Some(total) if total > 0 => format!(
+            "{} of {} ({}%)",
+            format_bytes(copied),
+            format_bytes(total),
+            (copied.min(total) * 100 / total),
+        ),
+        _ => format_bytes(copied),
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.1} {}", UNITS[unit])
+}
 
+#[cfg(test)]
+mod tests;
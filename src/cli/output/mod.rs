@@ -0,0 +1,128 @@
+use clap::ValueEnum;
+use color_eyre::Result;
+use serde::Serialize;
+
+use crate::kubectl::{self, KubectlRunner};
+
+/// How `qk match`, `qk status` and `qk list` print the pods they find.
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Yaml,
+    /// Aligned columns, for people.
+    Table,
+    /// `pod/<name>`, one per line, like `kubectl get -o name`.
+    Name,
+}
+
+/// A pod as qk resolved it. This is the schema of `--output json` and `--output yaml`, so fields may be added
+/// but never renamed or removed.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PodReport {
+    pub name: String,
+    pub namespace: String,
+    /// The resource managing the pod, e.g. `deployment/api`.
+    pub owner: String,
+    pub containers: Vec<String>,
+    /// The pod's status without decoration, e.g. `Running` or `CrashLoopBackOff`.
+    pub status: String,
+}
+
+impl PodReport {
+    /// Reports on a pod from its JSON. Every command reports from the same JSON in the same way, so a pod is
+    /// reported the same however it was found.
+    ///
+    /// # Arguments
+    /// * `pod` - The pod, as in `kubectl get pod -o json` or an item of `kubectl get pods -o json`.
+    pub fn from_json(pod: &serde_json::Value) -> Self {
+        PodReport {
+            name: pod["metadata"]["name"].as_str().unwrap_or_default().to_string(),
+            namespace: pod["metadata"]["namespace"].as_str().unwrap_or_default().to_string(),
+            owner: kubectl::pod_owner(pod),
+            containers: pod["spec"]["containers"].as_array().into_iter().flatten()
+                .filter_map(|container| container["name"].as_str())
+                .map(str::to_string)
+                .collect(),
+            status: kubectl::pod_status(pod),
+        }
+    }
+}
+
+/// Reports on the pod qk would open for a matcher, for `qk match` and `qk status`.
+///
+/// # Errors
+/// Returns an error if no pod matches or it can't be read.
+pub fn match_report(runner: &dyn KubectlRunner, matcher: &str) -> Result<PodReport> {
+    let pod = kubectl::find_matching_pod(runner, matcher)?;
+
+    Ok(PodReport::from_json(&kubectl::get_pod_json(runner, &pod.name, &pod.namespace)?))
+}
+
+/// Reports on every pod whose name matches a regex, for `qk list`. The pods are all read at once, so one
+/// going away part way through doesn't matter.
+///
+/// # Errors
+/// Returns an error if the matcher isn't a valid regex or the pods can't be listed.
+pub fn list_reports(runner: &dyn KubectlRunner, matcher: &str) -> Result<Vec<PodReport>> {
+    Ok(kubectl::find_matching_pods(runner, matcher)?.iter()
+        .map(PodReport::from_json)
+        .collect())
+}
+
+/// Formats a single pod, as a JSON or YAML object rather than a list of one.
+///
+/// # Errors
+/// Returns an error if the pod can't be serialised.
+pub fn format_pod(report: &PodReport, format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(report)?),
+        OutputFormat::Yaml => Ok(serde_yaml::to_string(report)?.trim_end().to_string()),
+        OutputFormat::Table | OutputFormat::Name => format_pods(std::slice::from_ref(report), format),
+    }
+}
+
+/// Formats a list of pods. JSON and YAML are always a list, even when it's empty or has one pod.
+///
+/// # Errors
+/// Returns an error if the pods can't be serialised.
+pub fn format_pods(reports: &[PodReport], format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(reports)?),
+        OutputFormat::Yaml => Ok(serde_yaml::to_string(reports)?.trim_end().to_string()),
+        OutputFormat::Table => Ok(format_table(reports)),
+        OutputFormat::Name => Ok(reports.iter().map(|report| format!("pod/{}", report.name)).collect::<Vec<_>>().join("\n")),
+    }
+}
+
+fn format_table(reports: &[PodReport]) -> String {
+    let header = ["NAMESPACE", "NAME", "OWNER", "CONTAINERS", "STATUS"].map(str::to_string);
+    let rows: Vec<[String; 5]> = std::iter::once(header)
+        .chain(reports.iter().map(|report| [
+            report.namespace.clone(),
+            report.name.clone(),
+            report.owner.clone(),
+            report.containers.join(","),
+            report.status.clone(),
+        ]))
+        .collect();
+
+    let mut widths = [0; 5];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    rows.iter()
+        .map(|row| {
+            let line: Vec<String> = row.iter().zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect();
+            line.join("   ").trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests;
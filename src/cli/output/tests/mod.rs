@@ -0,0 +1,147 @@
+use super::*;
+
+use std::collections::HashMap;
+
+fn report(name: &str, containers: &[&str], status: &str) -> PodReport {
+    PodReport {
+        name: name.to_string(),
+        namespace: "prod".to_string(),
+        owner: "deployment/api".to_string(),
+        containers: containers.iter().map(|container| container.to_string()).collect(),
+        status: status.to_string(),
+    }
+}
+
+#[test]
+fn test_format_pod_json() {
+    let json = format_pod(&report("api-7d9f8-abcde", &["api", "envoy"], "Running"), OutputFormat::Json).unwrap();
+
+    assert_eq!(serde_json::json!({
+        "name": "api-7d9f8-abcde",
+        "namespace": "prod",
+        "owner": "deployment/api",
+        "containers": ["api", "envoy"],
+        "status": "Running",
+    }), serde_json::from_str::<serde_json::Value>(&json).unwrap());
+}
+
+#[test]
+fn test_format_pod_yaml() {
+    let yaml = format_pod(&report("api-7d9f8-abcde", &["api"], "Running"), OutputFormat::Yaml).unwrap();
+
+    assert_eq!("\
+name: api-7d9f8-abcde
+namespace: prod
+owner: deployment/api
+containers:
+- api
+status: Running", yaml);
+}
+
+#[test]
+fn test_format_pods_json_is_always_a_list() {
+    assert_eq!("[]", format_pods(&[], OutputFormat::Json).unwrap());
+
+    let json = format_pods(&[report("api-7d9f8-abcde", &["api"], "Running")], OutputFormat::Json).unwrap();
+    assert_eq!(1, serde_json::from_str::<serde_json::Value>(&json).unwrap().as_array().unwrap().len());
+}
+
+#[test]
+fn test_format_pods_table() {
+    let table = format_pods(&[
+        report("api-7d9f8-abcde", &["api", "envoy"], "Running"),
+        report("api-7d9f8-fghij", &["api"], "CrashLoopBackOff"),
+    ], OutputFormat::Table).unwrap();
+
+    assert_eq!("\
+NAMESPACE   NAME              OWNER            CONTAINERS   STATUS
+prod        api-7d9f8-abcde   deployment/api   api,envoy    Running
+prod        api-7d9f8-fghij   deployment/api   api          CrashLoopBackOff", table);
+}
+
+#[test]
+fn test_format_pods_name() {
+    let names = format_pods(&[
+        report("api-7d9f8-abcde", &["api"], "Running"),
+        report("api-7d9f8-fghij", &["api"], "Running"),
+    ], OutputFormat::Name).unwrap();
+
+    assert_eq!("pod/api-7d9f8-abcde\npod/api-7d9f8-fghij", names);
+    assert_eq!("pod/api-7d9f8-abcde", format_pod(&report("api-7d9f8-abcde", &["api"], "Running"), OutputFormat::Name).unwrap());
+}
+
+/// Answers each kubectl command from a table, whatever order they come in.
+struct ClusterTestKubeCtlRunner(HashMap<String, String>);
+
+impl ClusterTestKubeCtlRunner {
+    fn new(outputs: &[(&str, &str)]) -> Self {
+        ClusterTestKubeCtlRunner(outputs.iter().map(|(command, output)| (command.to_string(), output.to_string())).collect())
+    }
+}
+
+impl KubectlRunner for ClusterTestKubeCtlRunner {
+    fn run_commands(&self, args: &[&str]) -> Result<String> {
+        let command = args.join(" ");
+        self.0.get(&command)
+            .cloned()
+            .ok_or_else(|| color_eyre::eyre::eyre!("unexpected kubectl {command}"))
+    }
+
+    fn spawn_shell(&self, _args: &[&str]) -> Result<()> {
+        unreachable!()
+    }
+
+    fn spawn_background(&self, _args: &[&str]) -> Result<Box<dyn kubectl::BackgroundCommand>> {
+        unreachable!()
+    }
+}
+
+const API_POD: &str = r#"{
+    "metadata": {"name": "api-7d9f8-abcde", "namespace": "prod", "labels": {"pod-template-hash": "7d9f8"},
+        "ownerReferences": [{"kind": "ReplicaSet", "name": "api-7d9f8"}]},
+    "spec": {"containers": [{"name": "api"}, {"name": "envoy"}]},
+    "status": {"phase": "Running", "containerStatuses": [{"name": "api", "state": {"running": {}}}, {"name": "envoy", "state": {"running": {}}}]}
+}"#;
+
+const CRASHING_API_POD: &str = r#"{
+    "metadata": {"name": "api-7d9f8-fghij", "namespace": "prod", "labels": {"pod-template-hash": "7d9f8"},
+        "ownerReferences": [{"kind": "ReplicaSet", "name": "api-7d9f8"}]},
+    "spec": {"containers": [{"name": "api"}]},
+    "status": {"phase": "Running", "containerStatuses": [{"name": "api", "state": {"waiting": {"reason": "CrashLoopBackOff"}}}]}
+}"#;
+
+const WEB_POD: &str = r#"{"metadata": {"name": "web-5c6b7-fghij", "namespace": "prod"}, "status": {"phase": "Running"}}"#;
+
+#[test]
+fn test_match_and_list_report_a_pod_the_same() {
+    let pod_list = format!(r#"{{"items": [{API_POD}, {WEB_POD}]}}"#);
+    let runner = ClusterTestKubeCtlRunner::new(&[
+        ("get pods --all-namespaces", "prod api-7d9f8-abcde"),
+        ("get deployments,rc,rs,ds -n prod", "NAME   READY\ndeployment.apps/api   1/1"),
+        ("get pods --all-namespaces -o json", &pod_list),
+        ("get pod api-7d9f8-abcde -n prod -o json", API_POD),
+    ]);
+
+    let matched = match_report(&runner, "api").unwrap();
+    let listed = list_reports(&runner, "^api").unwrap();
+
+    assert_eq!(report("api-7d9f8-abcde", &["api", "envoy"], "Running"), matched);
+    assert_eq!(
+        format_pods(std::slice::from_ref(&matched), OutputFormat::Json).unwrap(),
+        format_pods(&listed, OutputFormat::Json).unwrap()
+    );
+}
+
+#[test]
+fn test_list_reports_reads_every_pod_at_once() {
+    // Any other kubectl command would fail the listing.
+    let pod_list = format!(r#"{{"items": [{API_POD}, {CRASHING_API_POD}, {WEB_POD}]}}"#);
+    let runner = ClusterTestKubeCtlRunner::new(&[("get pods --all-namespaces -o json", &pod_list)]);
+
+    let listed = list_reports(&runner, "^api").unwrap();
+
+    assert_eq!(vec![
+        report("api-7d9f8-abcde", &["api", "envoy"], "Running"),
+        report("api-7d9f8-fghij", &["api"], "CrashLoopBackOff"),
+    ], listed);
+}
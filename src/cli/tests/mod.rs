@@ -0,0 +1,160 @@
+use super::*;
+
+#[test]
+fn test_parse_transfer_download() {
+    let (matcher, transfer) = parse_transfer("api:/tmp/heap.hprof", "./heap.hprof").unwrap();
+
+    assert_eq!("api", matcher);
+    assert_eq!(Transfer::Download { remote: "/tmp/heap.hprof".to_string(), local: "./heap.hprof".to_string() }, transfer);
+}
+
+#[test]
+fn test_parse_transfer_upload() {
+    let (matcher, transfer) = parse_transfer("./conf/a:b.yaml", "api:/etc/app.yaml").unwrap();
+
+    assert_eq!("api", matcher);
+    assert_eq!(Transfer::Upload { local: "./conf/a:b.yaml".to_string(), remote: "/etc/app.yaml".to_string() }, transfer);
+}
+
+#[test]
+fn test_parse_transfer_failure() {
+    assert!(parse_transfer("./a", "./b").is_err());
+    assert!(parse_transfer("api:/a", "web:/b").is_err());
+    assert!(parse_transfer("api:", "./b").is_err());
+}
+
+#[test]
+fn test_format_progress() {
+    assert_eq!("512 B", format_progress(512, None));
+    assert_eq!("1.5 MiB of 4.0 MiB (37%)", format_progress(1536 * 1024, Some(4 * 1024 * 1024)));
+    assert_eq!("2.0 GiB of 1.0 GiB (100%)", format_progress(2 << 30, Some(1 << 30)));
+}
+
+#[test]
+fn test_local_size() {
+    let dir = std::env::temp_dir().join("qk-cli-test-local-size");
+    fs::create_dir_all(dir.join("nested")).unwrap();
+    fs::write(dir.join("a"), [0u8; 10]).unwrap();
+    fs::write(dir.join("nested/b"), [0u8; 5]).unwrap();
+
+    assert_eq!(15, local_size(&dir));
+    assert_eq!(10, local_size(&dir.join("a")));
+    assert_eq!(0, local_size(&dir.join("missing")));
+}
+
+/// Measures everything in the pod as `remote_size` and records what's spawned, finishing the copy after a
+/// number of polls.
+struct CopyTestKubeCtlRunner {
+    remote_size: &'static str,
+    polls_to_finish: usize,
+    error: Option<&'static str>,
+    spawned: std::cell::RefCell<Vec<Vec<String>>>,
+}
+
+impl CopyTestKubeCtlRunner {
+    fn new(remote_size: &'static str, polls_to_finish: usize, error: Option<&'static str>) -> Self {
+        Self { remote_size, polls_to_finish, error, spawned: Default::default() }
+    }
+}
+
+impl KubectlRunner for CopyTestKubeCtlRunner {
+    fn run_commands(&self, args: &[&str]) -> Result<String> {
+        assert!(args.contains(&"du"), "unexpected kubectl {args:?}");
+        Ok(self.remote_size.to_string())
+    }
+
+    fn spawn_shell(&self, _args: &[&str]) -> Result<()> {
+        unreachable!()
+    }
+
+    fn spawn_background(&self, args: &[&str]) -> Result<Box<dyn BackgroundCommand>> {
+        self.spawned.borrow_mut().push(args.iter().map(|arg| arg.to_string()).collect());
+        Ok(Box::new(ScriptedCommand { polls_left: self.polls_to_finish, error: self.error }))
+    }
+}
+
+struct ScriptedCommand {
+    polls_left: usize,
+    error: Option<&'static str>,
+}
+
+impl BackgroundCommand for ScriptedCommand {
+    fn try_finish(&mut self) -> Option<Result<()>> {
+        if self.polls_left > 0 {
+            self.polls_left -= 1;
+            return None;
+        }
+        Some(self.error.map_or(Ok(()), |error| Err(eyre!(error))))
+    }
+}
+
+fn pod() -> FoundPod {
+    FoundPod { name: "api-abc".to_string(), namespace: "prod".to_string(), deployment: "deployment/api".to_string() }
+}
+
+#[test]
+fn test_copy_job_download_success() {
+    let dir = tempfile::tempdir().unwrap();
+    let local = dir.path().join("heap.hprof").to_string_lossy().to_string();
+    let transfer = Transfer::Download { remote: "/tmp/heap.hprof".to_string(), local: local.clone() };
+    let runner = CopyTestKubeCtlRunner::new("4\t/tmp/heap.hprof", 1, None);
+
+    let mut job = CopyJob::start(&runner, &pod(), Some("app"), transfer.clone()).unwrap();
+
+    assert_eq!(vec![kubectl::cp_args(&pod(), Some("app"), &transfer)], *runner.spawned.borrow());
+    assert_eq!(Some(4096), job.total);
+    assert!(job.poll(&runner).is_none());
+    assert!(job.poll(&runner).unwrap().is_ok());
+    assert_eq!(format!("api-abc:/tmp/heap.hprof -> {local} 4.0 KiB of 4.0 KiB (100%)"), job.progress());
+}
+
+#[test]
+fn test_copy_job_failure_reports_kubectl_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let transfer = Transfer::Download { remote: "/tmp/missing".to_string(), local: dir.path().join("missing").to_string_lossy().to_string() };
+    let runner = CopyTestKubeCtlRunner::new("", 0, Some("tar: /tmp/missing: No such file or directory"));
+
+    let mut job = CopyJob::start(&runner, &pod(), None, transfer).unwrap();
+
+    assert_eq!(None, job.total);
+    assert_eq!(
+        "kubectl cp failed: tar: /tmp/missing: No such file or directory",
+        job.poll(&runner).unwrap().unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn test_copy_job_progress_leaves_out_what_was_already_there() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("old.log"), [0u8; 100]).unwrap();
+    let transfer = Transfer::Download { remote: "/var/log/app".to_string(), local: dir.path().to_string_lossy().to_string() };
+    let runner = CopyTestKubeCtlRunner::new("2\t/var/log/app", 5, None);
+
+    let mut job = CopyJob::start(&runner, &pod(), None, transfer).unwrap();
+    job.progress_rate = Duration::ZERO;
+    fs::write(dir.path().join("app.log"), [0u8; 1024]).unwrap();
+
+    assert!(job.poll(&runner).is_none());
+    assert_eq!(1024, job.copied);
+}
+
+#[test]
+fn test_copy_job_upload_missing_file_failure() {
+    let transfer = Transfer::Upload { local: "./no-such-file".to_string(), remote: "/tmp/file".to_string() };
+    let runner = CopyTestKubeCtlRunner::new("", 0, None);
+
+    assert!(CopyJob::start(&runner, &pod(), None, transfer).is_err());
+    assert!(runner.spawned.borrow().is_empty());
+}
+
+#[test]
+fn test_copy_success() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("app.yaml"), "replicas: 2\n").unwrap();
+    let transfer = Transfer::Upload { local: dir.path().join("app.yaml").to_string_lossy().to_string(), remote: "/etc/app.yaml".to_string() };
+    let runner = CopyTestKubeCtlRunner::new("", 0, None);
+
+    copy(&runner, &pod(), None, transfer).unwrap();
+
+    assert_eq!(1, runner.spawned.borrow().len());
+}
@@ -0,0 +1,178 @@
+use crossterm::event::KeyCode;
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum InternalAction {
+    FetchLogs,
+    LastLogs,
+    ViewDesc,
+    Events,
+    Env,
+    RevealSecrets,
+    Metrics,
+    Diagnose,
+    Purge,
+    World,
+    WorldAll,
+    WorldBreakdown,
+    Switch,
+    Exec,
+    ExecCommand,
+    CopyFiles,
+    Edit,
+    EditConfigMap,
+    Debug,
+    Vim,
+    CopyPodName,
+    CopyPodPath,
+    CopyVisible,
+    CopyBuffer,
+    CopyMatches,
+    NewTab,
+    CloseTab,
+    CloseOtherTabs,
+    NextTab,
+    PreviousTab,
+    SplitSideBySide,
+    SplitStacked,
+    SinglePane,
+    FocusOtherPane,
+    RefreshPanes,
+    ToggleSidebar,
+    SelectNextPod,
+    SelectPreviousPod,
+    SwitchToSelectedPod,
+    ToggleWrap,
+    ScrollDown,
+    ScrollUp,
+    PageDown,
+    PageUp,
+    ScrollRight,
+    ScrollLeft,
+    ScrollToTop,
+    ScrollToBottom,
+    Help,
+    CommandPalette,
+    Quit,
+}
+
+/// An entry in the action registry, which drives key bindings, the help overlay and the command palette.
+pub struct ActionSpec {
+    pub action: InternalAction,
+    /// Keys bound to the action. Empty for actions that are only reachable from the command palette,
+    /// or that are bound contextually (e.g. sidebar navigation).
+    pub keys: &'static [KeyCode],
+    /// How the keys are shown in the help overlay, e.g. `j/↓`.
+    pub key_label: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+const fn spec(action: InternalAction, keys: &'static [KeyCode], key_label: &'static str, name: &'static str, description: &'static str) -> ActionSpec {
+    ActionSpec { action, keys, key_label, name, description }
+}
+
+/// Every action available in the TUI, in the order they're listed in the help overlay.
+pub const ACTIONS: &[ActionSpec] = &[
+    spec(InternalAction::FetchLogs, &[KeyCode::Char('f')], "f", "fetch logs", "Show the latest logs of the pod"),
+    spec(InternalAction::LastLogs, &[KeyCode::Char('l')], "l", "last logs", "Show logs from the previous container instance"),
+    spec(InternalAction::ViewDesc, &[KeyCode::Char('d')], "d", "describe", "Show `kubectl describe` for the pod"),
+    spec(InternalAction::Events, &[KeyCode::Char('n')], "n", "events", "Show events involving the pod"),
+    spec(InternalAction::Env, &[KeyCode::Char('V')], "V", "env", "Show each container's environment variables and mounted ConfigMaps and Secrets"),
+    spec(InternalAction::RevealSecrets, &[KeyCode::Char('R')], "R", "reveal secrets", "Show or mask secret values in the env view"),
+    spec(InternalAction::Metrics, &[KeyCode::Char('u')], "u", "usage", "Show CPU and memory usage against requests and limits"),
+    spec(InternalAction::Diagnose, &[KeyCode::Char('r')], "r", "crash diagnostics", "Show why the pod's containers keep restarting"),
+    spec(InternalAction::World, &[KeyCode::Char('w')], "w", "world", "List pods in the namespace with a status chart"),
+    spec(InternalAction::WorldAll, &[KeyCode::Char('W')], "W", "world (all)", "List all resources in the namespace"),
+    spec(InternalAction::WorldBreakdown, &[KeyCode::Char('B')], "B", "world breakdown", "Break the world chart down by phase, namespace or owner"),
+    spec(InternalAction::Vim, &[KeyCode::Char('v')], "v", "vim logs", "Open the full logs in vim"),
+    spec(InternalAction::CopyPodName, &[KeyCode::Char('y')], "y", "copy pod name", "Copy the pod's name to the clipboard"),
+    spec(InternalAction::CopyPodPath, &[KeyCode::Char('Y')], "Y", "copy namespace/name", "Copy the pod's namespace/name to the clipboard"),
+    spec(InternalAction::CopyVisible, &[KeyCode::Char('c')], "c", "copy visible lines", "Copy the lines visible in the focused pane"),
+    spec(InternalAction::CopyBuffer, &[KeyCode::Char('C')], "C", "copy whole buffer", "Copy everything in the focused pane"),
+    spec(InternalAction::CopyMatches, &[KeyCode::Char('m')], "m", "copy matching lines", "Copy the lines in the focused pane matching a regex"),
+    spec(InternalAction::Exec, &[KeyCode::Char('e')], "e", "exec", "Open a shell in the pod, trying each configured shell in turn"),
+    spec(InternalAction::ExecCommand, &[KeyCode::Char('X')], "X", "exec command", "Run a command of your choice in the pod"),
+    spec(InternalAction::CopyFiles, &[KeyCode::Char('F')], "F", "copy files", "Browse the pod's files to copy them to or from it"),
+    spec(InternalAction::Edit, &[KeyCode::Char('E')], "E", "edit", "Edit the pod's deployment"),
+    spec(InternalAction::EditConfigMap, &[KeyCode::Char('M')], "M", "edit configmap", "Edit a ConfigMap the pod uses"),
+    spec(InternalAction::Debug, &[KeyCode::Char('b')], "b", "debug", "Debug the pod, a copy of it or its node with a debug image"),
+    spec(InternalAction::Purge, &[KeyCode::Char('p')], "p", "purge", "Delete the pod"),
+    spec(InternalAction::Switch, &[KeyCode::Char('s')], "s", "switch", "Switch this tab to another pod by matcher"),
+    spec(InternalAction::NewTab, &[KeyCode::Char('t')], "t", "new tab", "Open a pod in a new tab by matcher"),
+    spec(InternalAction::CloseTab, &[KeyCode::Char('x')], "x", "close tab", "Close the current tab"),
+    spec(InternalAction::CloseOtherTabs, &[], "", "close other tabs", "Close every tab except the current one"),
+    spec(InternalAction::NextTab, &[KeyCode::Tab], "⇥", "next tab", "Cycle to the next tab"),
+    spec(InternalAction::PreviousTab, &[KeyCode::BackTab], "⇧⇥", "previous tab", "Cycle to the previous tab"),
+    spec(InternalAction::SplitSideBySide, &[KeyCode::Char('|')], "|", "split side by side", "Toggle a second pane beside the first"),
+    spec(InternalAction::SplitStacked, &[KeyCode::Char('-')], "-", "split stacked", "Toggle a second pane below the first"),
+    spec(InternalAction::SinglePane, &[], "", "single pane", "Go back to a single pane"),
+    spec(InternalAction::FocusOtherPane, &[KeyCode::Char('o')], "o", "other pane", "Move focus to the other pane"),
+    spec(InternalAction::RefreshPanes, &[], "", "refresh panes", "Reload what every visible pane is showing"),
+    spec(InternalAction::ToggleSidebar, &[KeyCode::Char('S')], "S", "sidebar", "Toggle the pod list sidebar"),
+    spec(InternalAction::SelectNextPod, &[], "↓ (sidebar)", "select next pod", "Move the sidebar selection down"),
+    spec(InternalAction::SelectPreviousPod, &[], "↑ (sidebar)", "select previous pod", "Move the sidebar selection up"),
+    spec(InternalAction::SwitchToSelectedPod, &[], "⏎ (sidebar)", "switch to selected pod", "Switch this tab to the pod selected in the sidebar"),
+    spec(InternalAction::ToggleWrap, &[KeyCode::Char('z')], "z", "wrap", "Toggle line wrapping"),
+    spec(InternalAction::ScrollDown, &[KeyCode::Char('j'), KeyCode::Down], "j/↓", "scroll down", "Scroll the focused pane down a line"),
+    spec(InternalAction::ScrollUp, &[KeyCode::Char('k'), KeyCode::Up], "k/↑", "scroll up", "Scroll the focused pane up a line"),
+    spec(InternalAction::PageDown, &[KeyCode::PageDown], "PgDn", "page down", "Scroll the focused pane down a page"),
+    spec(InternalAction::PageUp, &[KeyCode::PageUp], "PgUp", "page up", "Scroll the focused pane up a page"),
+    spec(InternalAction::ScrollRight, &[KeyCode::Right], "→", "scroll right", "Scroll the focused pane right when not wrapping"),
+    spec(InternalAction::ScrollLeft, &[KeyCode::Left], "←", "scroll left", "Scroll the focused pane left when not wrapping"),
+    spec(InternalAction::ScrollToTop, &[KeyCode::Char('g'), KeyCode::Home], "g/Home", "scroll to top", "Jump to the start of the focused pane"),
+    spec(InternalAction::ScrollToBottom, &[KeyCode::Char('G'), KeyCode::End], "G/End", "scroll to bottom", "Jump to the end of the focused pane"),
+    spec(InternalAction::Help, &[KeyCode::Char('?')], "?", "help", "Show this list of actions"),
+    spec(InternalAction::CommandPalette, &[KeyCode::Char(':')], ":", "command palette", "Search for an action to run"),
+    spec(InternalAction::Quit, &[KeyCode::Char('q')], "q", "quit", "Quit qk"),
+];
+
+/// Looks up the action bound to a key, if any.
+pub fn action_for_key(key: KeyCode) -> Option<InternalAction> {
+    ACTIONS.iter()
+        .find(|spec| spec.keys.contains(&key))
+        .map(|spec| spec.action)
+}
+
+/// Scores how well `query` fuzzy-matches `candidate`, or `None` if the characters of `query` don't
+/// all appear in order in `candidate`. Consecutive characters and matches at word starts score higher.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for query_char in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = candidate[position..].iter().position(|c| *c == query_char)? + position;
+
+        score += 1;
+        if previous_match.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !candidate[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+
+        previous_match = Some(found);
+        position = found + 1;
+    }
+
+    Some(score)
+}
+
+/// Finds the actions matching a command palette query, best matches first.
+pub fn search(query: &str) -> Vec<&'static ActionSpec> {
+    let mut matches: Vec<(i32, &ActionSpec)> = ACTIONS.iter()
+        .filter_map(|spec| {
+            // Names are what people type, so they outrank a match on the description.
+            let name_score = fuzzy_score(query, spec.name).map(|score| score * 2);
+            let description_score = fuzzy_score(query, spec.description);
+            name_score.max(description_score).map(|score| (score, spec))
+        })
+        .collect();
+
+    matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+    matches.into_iter().map(|(_, spec)| spec).collect()
+}
+
+#[cfg(test)]
+mod tests;
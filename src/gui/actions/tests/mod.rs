@@ -0,0 +1,48 @@
+use super::*;
+
+#[test]
+fn test_action_for_key_success() {
+    assert_eq!(Some(InternalAction::FetchLogs), action_for_key(KeyCode::Char('f')));
+    assert_eq!(Some(InternalAction::ScrollDown), action_for_key(KeyCode::Down));
+}
+
+#[test]
+fn test_action_for_key_unbound() {
+    assert_eq!(None, action_for_key(KeyCode::Char('Q')));
+}
+
+#[test]
+fn test_actions_have_unique_keys() {
+    let keys: Vec<&KeyCode> = ACTIONS.iter().flat_map(|spec| spec.keys).collect();
+
+    for (index, key) in keys.iter().enumerate() {
+        assert!(!keys[index + 1..].contains(key), "{key:?} is bound to more than one action");
+    }
+}
+
+#[test]
+fn test_fuzzy_score_matches_in_order() {
+    assert!(fuzzy_score("flg", "fetch logs").is_some());
+    assert!(fuzzy_score("gfl", "fetch logs").is_none());
+    assert!(fuzzy_score("", "fetch logs").is_some());
+}
+
+#[test]
+fn test_fuzzy_score_prefers_consecutive_and_word_starts() {
+    let consecutive = fuzzy_score("log", "fetch logs").unwrap();
+    let scattered = fuzzy_score("log", "close other tabs go").unwrap_or(0);
+
+    assert!(consecutive > scattered);
+}
+
+#[test]
+fn test_search_ranks_best_match_first() {
+    let results = search("close other");
+
+    assert_eq!(InternalAction::CloseOtherTabs, results[0].action);
+}
+
+#[test]
+fn test_search_empty_query_lists_every_action() {
+    assert_eq!(ACTIONS.len(), search("").len());
+}
@@ -0,0 +1,95 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use crate::settings::{self, ClipboardMode};
+
+/// Terminals drop OSC 52 sequences much bigger than this, so larger copies go to a file instead.
+const MAX_OSC52_LENGTH: usize = 100_000;
+/// The file in qk's config directory that copies go to when they can't reach the clipboard.
+const CLIPBOARD_FILE: &str = "clipboard";
+
+/// Where copied text ended up.
+#[derive(PartialEq, Debug)]
+pub enum Copied {
+    Clipboard,
+    File(PathBuf),
+}
+
+/// Copies text to the clipboard with the OSC 52 escape sequence. The terminal does the copying, so this
+/// works without a display server, over SSH and inside tmux or screen.
+///
+/// # Arguments
+///
+/// * `text` - The text to copy.
+/// * `mode` - Whether to try the clipboard at all, or go straight to the file.
+///
+/// # Returns
+///
+/// * `Copied::Clipboard` - If the escape sequence was sent.
+/// * `Copied::File(PathBuf)` - If the text was written to `clipboard` in qk's config directory instead,
+///   because of `mode`, the size of the text, or stdout not being writable.
+///
+/// # Errors
+///
+/// Returns an error if falling back to the file fails too, or there's no home directory to put it in.
+pub fn copy(text: &str, mode: ClipboardMode) -> io::Result<Copied> {
+    let encoded = STANDARD.encode(text);
+
+    if mode == ClipboardMode::Osc52 && encoded.len() <= MAX_OSC52_LENGTH {
+        let sequence = osc52_sequence(&encoded, env::var_os("TMUX").is_some(), env::var("TERM").is_ok_and(|term| term.starts_with("screen")));
+        let mut stdout = io::stdout();
+        if stdout.write_all(sequence.as_bytes()).and_then(|_| stdout.flush()).is_ok() {
+            return Ok(Copied::Clipboard);
+        }
+    }
+
+    let dir = settings::config_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No home directory to write the clipboard file in"))?;
+    write_file(&dir, text).map(Copied::File)
+}
+
+/// Writes copied text to a file only the user can read, as it may hold secrets revealed in the env view.
+/// Any file or symlink already at the path is replaced rather than written through.
+fn write_file(dir: &Path, text: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(CLIPBOARD_FILE);
+
+    match fs::remove_file(&path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+        _ => {}
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(&path)?.write_all(text.as_bytes())?;
+
+    Ok(path)
+}
+
+/// Builds the OSC 52 sequence for base64 encoded text. Terminal multiplexers only pass it on to the outer
+/// terminal when it's wrapped in their own passthrough sequence.
+fn osc52_sequence(encoded: &str, in_tmux: bool, in_screen: bool) -> String {
+    let sequence = format!("\x1b]52;c;{encoded}\x07");
+
+    if in_tmux {
+        // Needs `set -g allow-passthrough on` in tmux 3.3 and later.
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else if in_screen {
+        format!("\x1bP{sequence}\x1b\\")
+    } else {
+        sequence
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,46 @@
+use super::*;
+
+#[test]
+fn test_osc52_sequence_plain() {
+    assert_eq!("\x1b]52;c;cG9k\x07", osc52_sequence("cG9k", false, false));
+}
+
+#[test]
+fn test_osc52_sequence_tmux_passthrough() {
+    assert_eq!("\x1bPtmux;\x1b\x1b]52;c;cG9k\x07\x1b\\", osc52_sequence("cG9k", true, false));
+}
+
+#[test]
+fn test_osc52_sequence_screen_passthrough() {
+    assert_eq!("\x1bP\x1b]52;c;cG9k\x07\x1b\\", osc52_sequence("cG9k", false, true));
+}
+
+#[test]
+fn test_write_file_replaces_previous_copy() {
+    let dir = tempfile::tempdir().unwrap();
+
+    write_file(dir.path(), "a much longer previous copy").unwrap();
+    let path = write_file(dir.path(), "my-pod-abc123").unwrap();
+
+    assert_eq!(dir.path().join(CLIPBOARD_FILE), path);
+    assert_eq!("my-pod-abc123", fs::read_to_string(&path).unwrap());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(0o600, fs::metadata(&path).unwrap().permissions().mode() & 0o777);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_write_file_does_not_follow_symlink() {
+    let dir = tempfile::tempdir().unwrap();
+    let target = dir.path().join("elsewhere");
+    fs::write(&target, "untouched").unwrap();
+    std::os::unix::fs::symlink(&target, dir.path().join(CLIPBOARD_FILE)).unwrap();
+
+    let path = write_file(dir.path(), "my-pod-abc123").unwrap();
+
+    assert_eq!("my-pod-abc123", fs::read_to_string(path).unwrap());
+    assert_eq!("untouched", fs::read_to_string(target).unwrap());
+}
@@ -0,0 +1,135 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Clear, Row, Table};
+
+use crate::kubectl::{DebugMode, DebugOptions};
+
+use super::theme::Theme;
+
+/// The `kubectl debug` profiles on offer, least privileged first.
+pub const PROFILES: [&str; 3] = ["general", "netadmin", "sysadmin"];
+
+/// A choice in the debug popup.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Field {
+    Image,
+    Container,
+    Mode,
+    Profile,
+}
+
+const FIELDS: [Field; 4] = [Field::Image, Field::Container, Field::Mode, Field::Profile];
+
+/// The choices made so far in the debug popup, each as an index into its options.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DebugForm {
+    pub images: Vec<String>,
+    pub image: usize,
+    pub containers: Vec<String>,
+    pub container: usize,
+    pub mode: usize,
+    pub profile: usize,
+    /// The index of the focused field.
+    pub field: usize,
+}
+
+impl DebugForm {
+    pub fn new(images: Vec<String>, containers: Vec<String>) -> Self {
+        DebugForm { images, image: 0, containers, container: 0, mode: 0, profile: 0, field: 0 }
+    }
+
+    pub fn focused(&self) -> Field {
+        FIELDS[self.field]
+    }
+
+    /// Moves focus up or down the fields, wrapping around.
+    pub fn move_focus(&mut self, offset: isize) {
+        self.field = (self.field as isize + offset).rem_euclid(FIELDS.len() as isize) as usize;
+    }
+
+    /// Picks the previous or next option for the focused field, wrapping around.
+    pub fn cycle(&mut self, offset: isize) {
+        let (choice, count) = match self.focused() {
+            Field::Image => (&mut self.image, self.images.len()),
+            Field::Container => (&mut self.container, self.containers.len()),
+            Field::Mode => (&mut self.mode, DebugMode::ALL.len()),
+            Field::Profile => (&mut self.profile, PROFILES.len()),
+        };
+
+        if count > 0 {
+            *choice = (*choice as isize + offset).rem_euclid(count as isize) as usize;
+        }
+    }
+
+    pub fn mode(&self) -> DebugMode {
+        DebugMode::ALL[self.mode]
+    }
+
+    /// The options chosen, ready for `kubectl::debug_pod`. A target container only applies to ephemeral containers.
+    pub fn options(&self) -> DebugOptions {
+        DebugOptions {
+            image: self.images.get(self.image).cloned().unwrap_or_default(),
+            target_container: self.containers.get(self.container)
+                .filter(|_| self.mode() == DebugMode::Ephemeral)
+                .cloned(),
+            mode: self.mode(),
+            profile: PROFILES[self.profile].to_string(),
+        }
+    }
+
+    fn value(&self, field: Field) -> &str {
+        match field {
+            Field::Image => self.images.get(self.image).map_or("", String::as_str),
+            Field::Container => match self.mode() {
+                DebugMode::Ephemeral => self.containers.get(self.container).map_or("", String::as_str),
+                _ => "-",
+            },
+            Field::Mode => self.mode().label(),
+            Field::Profile => PROFILES[self.profile],
+        }
+    }
+}
+
+fn field_label(field: Field) -> &'static str {
+    match field {
+        Field::Image => "Image",
+        Field::Container => "Target container",
+        Field::Mode => "Debug in",
+        Field::Profile => "Profile",
+    }
+}
+
+/// Draws the debug popup over the given area.
+///
+/// # Arguments
+///
+/// * `form` - The choices to show, with the focused one highlighted.
+/// * `area` - Where to draw the popup.
+/// * `theme` - The colours to draw with.
+pub fn render(f: &mut Frame, form: &DebugForm, area: Rect, theme: &Theme) {
+    let rows = FIELDS.iter().enumerate().map(|(index, field)| {
+        let is_focused = index == form.field;
+        let value = if is_focused { format!("< {} >", form.value(*field)) } else { format!("  {}", form.value(*field)) };
+        let row = Row::new(vec![
+            Cell::from(field_label(*field)).fg(theme.muted),
+            Cell::from(value).fg(theme.foreground),
+        ]);
+
+        if is_focused { row.bg(theme.selection).bold() } else { row }
+    });
+
+    let table = Table::new(rows, [Constraint::Length(18), Constraint::Fill(1)])
+        .block(
+            Block::bordered().fg(theme.foreground)
+                .title_top(Line::from(theme.label("🐞 Debug (ESC to close)")).left_aligned().bold())
+                .title_bottom(Line::from(theme.label("↑↓ choose, ←→ change, ⏎ start")).right_aligned()))
+        .style(Style::default().bg(theme.background));
+
+    f.render_widget(Clear, area); //this clears out the background
+    f.render_widget(table, area);
+}
+
+#[cfg(test)]
+mod tests;
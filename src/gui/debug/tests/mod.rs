@@ -0,0 +1,66 @@
+use super::*;
+
+fn form() -> DebugForm {
+    DebugForm::new(
+        vec!["nicolaka/netshoot".to_string(), "busybox".to_string()],
+        vec!["app".to_string(), "sidecar".to_string()],
+    )
+}
+
+#[test]
+fn test_options_defaults() {
+    assert_eq!(DebugOptions {
+        image: "nicolaka/netshoot".to_string(),
+        target_container: Some("app".to_string()),
+        mode: DebugMode::Ephemeral,
+        profile: "general".to_string(),
+    }, form().options());
+}
+
+#[test]
+fn test_cycle_changes_focused_field() {
+    let mut form = form();
+
+    form.cycle(1);
+    form.move_focus(1);
+    form.cycle(-1);
+    form.move_focus(2);
+    form.cycle(1);
+
+    assert_eq!(DebugOptions {
+        image: "busybox".to_string(),
+        target_container: Some("sidecar".to_string()),
+        mode: DebugMode::Ephemeral,
+        profile: "netadmin".to_string(),
+    }, form.options());
+}
+
+#[test]
+fn test_move_focus_wraps() {
+    let mut form = form();
+
+    form.move_focus(-1);
+
+    assert_eq!(Field::Profile, form.focused());
+}
+
+#[test]
+fn test_options_only_targets_container_when_ephemeral() {
+    let mut form = form();
+    form.move_focus(2);
+
+    form.cycle(1);
+
+    assert_eq!(DebugMode::CopyPod, form.options().mode);
+    assert_eq!(None, form.options().target_container);
+}
+
+#[test]
+fn test_cycle_without_options() {
+    let mut form = DebugForm::new(vec!["busybox".to_string()], Vec::new());
+    form.move_focus(1);
+
+    form.cycle(1);
+
+    assert_eq!(None, form.options().target_container);
+}
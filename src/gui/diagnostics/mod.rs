@@ -0,0 +1,144 @@
+use std::time::{Duration, Instant};
+
+use crate::kubectl::{self, ContainerCrash, FoundPod, KubectlRunner};
+
+/// How often the diagnostics are refreshed while they're on screen. Crash loops back off for up to five
+/// minutes between restarts, so there's no point polling hard.
+const DIAGNOSTICS_REFRESH_RATE: Duration = Duration::from_secs(10);
+/// How much of the previous container's logs to show, which is usually where the stack trace is.
+const LOG_TAIL_LINES: usize = 20;
+/// How many of the most recent warning events to show.
+const MAX_WARNINGS: usize = 10;
+
+/// Why a pod's containers keep restarting, gathered from its status, its previous logs and its events.
+#[derive(Default)]
+pub struct Diagnostics {
+    pub containers: Vec<ContainerCrash>,
+    /// The pod's total restart count each time it was seen to change, oldest first.
+    pub restart_history: Vec<(Instant, u32)>,
+    /// The tail of the previous container's logs, or why they couldn't be fetched.
+    pub previous_logs: String,
+    pub warnings: String,
+    /// Why the pod's status couldn't be read, if it failed.
+    pub error: Option<String>,
+    pub last_sample: Option<Instant>,
+}
+
+impl Diagnostics {
+    pub fn is_due(&self) -> bool {
+        self.last_sample.is_none_or(|sampled| sampled.elapsed() >= DIAGNOSTICS_REFRESH_RATE)
+    }
+
+    /// Refreshes everything. The logs and events are best effort, as a pod that has never restarted has no
+    /// previous logs and events expire after an hour.
+    pub fn sample(&mut self, runner: &dyn KubectlRunner, pod: &FoundPod) {
+        let now = Instant::now();
+        self.last_sample = Some(now);
+
+        match kubectl::get_container_crashes(runner, pod) {
+            Ok(containers) => {
+                self.containers = containers;
+                self.record_restarts(now);
+                self.error = None;
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+
+        self.previous_logs = match kubectl::get_pod_logs(runner, pod, true, true) {
+            Ok(logs) => tail(&logs, LOG_TAIL_LINES),
+            Err(_) => "No logs from a previous container".to_string(),
+        };
+        self.warnings = match kubectl::get_pod_warnings(runner, pod) {
+            Ok(warnings) => tail(&warnings, MAX_WARNINGS),
+            Err(err) => err.to_string(),
+        };
+    }
+
+    /// Notes the current restart count if it has changed since it was last seen.
+    fn record_restarts(&mut self, now: Instant) {
+        let restarts = self.containers.iter().map(|container| container.restart_count).sum();
+        if self.restart_history.last().is_none_or(|(_, previous)| *previous != restarts) {
+            self.restart_history.push((now, restarts));
+        }
+    }
+
+    /// Everything gathered, as text for the pane.
+    pub fn report(&self, now: Instant) -> String {
+        if let Some(error) = &self.error {
+            return error.clone();
+        }
+
+        let mut report = vec!["Containers".to_string()];
+        for container in &self.containers {
+            let state = match &container.state_reason {
+                Some(reason) => format!("{}: {reason}", container.state),
+                None => container.state.clone(),
+            };
+            let kind = if container.init { "init container " } else { "" };
+            report.push(format!("  {kind}{} ({state}), {} restarts", container.name, container.restart_count));
+
+            if let Some(reason) = &container.last_reason {
+                let exit_code = container.last_exit_code
+                    .map(|code| format!(", exit code {code}{}", exit_code_hint(code).map(|hint| format!(" ({hint})")).unwrap_or_default()))
+                    .unwrap_or_default();
+                let finished_at = container.last_finished_at.as_deref()
+                    .map(|finished_at| format!(" at {finished_at}"))
+                    .unwrap_or_default();
+                report.push(format!("    last terminated: {reason}{exit_code}{finished_at}"));
+            }
+            if container.is_oom_killed() {
+                let limit = container.memory_limit.as_deref().unwrap_or("not set");
+                report.push(format!("    OOMKilled: the container ran out of memory, its limit is {limit}"));
+            }
+        }
+
+        report.push(String::new());
+        report.push("Restarts over time".to_string());
+        for (seen, restarts) in &self.restart_history {
+            report.push(format!("  {restarts} restarts, seen {}", format_ago(now.saturating_duration_since(*seen))));
+        }
+
+        report.push(String::new());
+        report.push(format!("Previous container logs (last {LOG_TAIL_LINES} lines)"));
+        report.extend(self.previous_logs.lines().map(|line| format!("  {line}")));
+
+        report.push(String::new());
+        report.push("Warning events".to_string());
+        if self.warnings.trim().is_empty() {
+            report.push("  None".to_string());
+        }
+        report.extend(self.warnings.lines().map(|line| format!("  {line}")));
+
+        report.join("\n")
+    }
+}
+
+/// What an exit code usually means, for the common ones.
+fn exit_code_hint(code: i64) -> Option<&'static str> {
+    match code {
+        0 => Some("exited normally"),
+        1 => Some("application error"),
+        126 => Some("command not executable"),
+        127 => Some("command not found"),
+        137 => Some("killed by SIGKILL"),
+        139 => Some("segmentation fault"),
+        143 => Some("terminated by SIGTERM"),
+        _ => None,
+    }
+}
+
+fn format_ago(elapsed: Duration) -> String {
+    match elapsed.as_secs() {
+        0 => "now".to_string(),
+        seconds if seconds < 60 => format!("{seconds}s ago"),
+        seconds => format!("{}m {}s ago", seconds / 60, seconds % 60),
+    }
+}
+
+fn tail(text: &str, lines: usize) -> String {
+    let all_lines: Vec<&str> = text.lines().collect();
+    all_lines[all_lines.len().saturating_sub(lines)..].join("\n")
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,70 @@
+use super::*;
+
+fn crash_looping(restart_count: u32) -> ContainerCrash {
+    ContainerCrash {
+        name: "app".to_string(),
+        restart_count,
+        state: "Waiting".to_string(),
+        state_reason: Some("CrashLoopBackOff".to_string()),
+        last_reason: Some("OOMKilled".to_string()),
+        last_exit_code: Some(137),
+        last_finished_at: Some("2024-05-01T10:00:00Z".to_string()),
+        memory_limit: Some("128Mi".to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_record_restarts_only_keeps_changes() {
+    let start = Instant::now();
+    let mut diagnostics = Diagnostics { containers: vec![crash_looping(3)], ..Default::default() };
+
+    diagnostics.record_restarts(start);
+    diagnostics.record_restarts(start + Duration::from_secs(10));
+    diagnostics.containers = vec![crash_looping(4)];
+    diagnostics.record_restarts(start + Duration::from_secs(20));
+
+    assert_eq!(vec![(start, 3), (start + Duration::from_secs(20), 4)], diagnostics.restart_history);
+}
+
+#[test]
+fn test_report() {
+    let start = Instant::now();
+    let diagnostics = Diagnostics {
+        containers: vec![crash_looping(4)],
+        restart_history: vec![(start, 3), (start + Duration::from_secs(90), 4)],
+        previous_logs: "panic: out of memory".to_string(),
+        ..Default::default()
+    };
+
+    let report = diagnostics.report(start + Duration::from_secs(90));
+
+    assert_eq!("\
+Containers
+  app (Waiting: CrashLoopBackOff), 4 restarts
+    last terminated: OOMKilled, exit code 137 (killed by SIGKILL) at 2024-05-01T10:00:00Z
+    OOMKilled: the container ran out of memory, its limit is 128Mi
+
+Restarts over time
+  3 restarts, seen 1m 30s ago
+  4 restarts, seen now
+
+Previous container logs (last 20 lines)
+  panic: out of memory
+
+Warning events
+  None", report);
+}
+
+#[test]
+fn test_report_error() {
+    let diagnostics = Diagnostics { error: Some("no such pod".to_string()), ..Default::default() };
+
+    assert_eq!("no such pod", diagnostics.report(Instant::now()));
+}
+
+#[test]
+fn test_tail() {
+    assert_eq!("b\nc", tail("a\nb\nc", 2));
+    assert_eq!("a", tail("a", 2));
+}
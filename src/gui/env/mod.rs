@@ -0,0 +1,59 @@
+use crate::kubectl::{ContainerEnv, EnvSource, EnvVar};
+
+/// Shown in place of secret values until they're revealed.
+const MASK: &str = "********";
+
+/// Lays out each container's environment variables and mounted config as text for the env view.
+///
+/// # Arguments
+///
+/// * `containers` - The environment of each container.
+/// * `reveal_secrets` - Whether to show secret values, rather than masking them.
+pub fn format(containers: &[ContainerEnv], reveal_secrets: bool) -> String {
+    let mut lines = Vec::new();
+
+    for container in containers {
+        lines.push(container.name.clone());
+
+        if !container.env.is_empty() {
+            lines.push("  env".to_string());
+            lines.extend(container.env.iter().map(|var| format!("    {}", format_var(var, reveal_secrets))));
+        }
+
+        for (kind, name, vars) in &container.env_from {
+            lines.push(format!("  env from {} {name}", kind.resource()));
+            if vars.is_empty() {
+                lines.push("    <not found>".to_string());
+            }
+            lines.extend(vars.iter().map(|var| format!("    {}", format_var(var, reveal_secrets))));
+        }
+
+        if !container.mounts.is_empty() {
+            lines.push("  mounts".to_string());
+            lines.extend(container.mounts.iter()
+                .map(|mount| format!("    {}  {} {}", mount.mount_path, mount.kind.resource(), mount.name)));
+        }
+
+        lines.push(String::new());
+    }
+
+    lines.join("\n").trim_end().to_string()
+}
+
+fn format_var(var: &EnvVar, reveal_secrets: bool) -> String {
+    let value = match (&var.value, &var.source) {
+        (Some(_), _) if var.is_secret() && !reveal_secrets => MASK.to_string(),
+        (Some(value), _) => value.clone(),
+        (None, EnvSource::Field(field)) => format!("<field {field}>"),
+        (None, EnvSource::Resource(resource)) => format!("<resource {resource}>"),
+        (None, _) => "<not found>".to_string(),
+    };
+
+    match &var.source {
+        EnvSource::Key { kind, name, key } => format!("{}={value}  ({} {name}/{key})", var.name, kind.resource()),
+        _ => format!("{}={value}", var.name),
+    }
+}
+
+#[cfg(test)]
+mod tests;
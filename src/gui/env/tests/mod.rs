@@ -0,0 +1,40 @@
+use super::*;
+use crate::kubectl::{ConfigKind, ConfigMount};
+
+fn container() -> ContainerEnv {
+    let secret_key = EnvSource::Key { kind: ConfigKind::Secret, name: "db-creds".to_string(), key: "password".to_string() };
+    ContainerEnv {
+        name: "app".to_string(),
+        env: vec![
+            EnvVar { name: "LOG_LEVEL".to_string(), source: EnvSource::Literal, value: Some("debug".to_string()) },
+            EnvVar { name: "DB_PASSWORD".to_string(), source: secret_key.clone(), value: Some("hunter2".to_string()) },
+            EnvVar { name: "POD_IP".to_string(), source: EnvSource::Field("status.podIP".to_string()), value: None },
+        ],
+        env_from: vec![(ConfigKind::Secret, "db-creds".to_string(), vec![
+            EnvVar { name: "password".to_string(), source: secret_key, value: Some("hunter2".to_string()) },
+        ])],
+        mounts: vec![ConfigMount { kind: ConfigKind::ConfigMap, name: "app-config".to_string(), mount_path: "/etc/app".to_string() }],
+    }
+}
+
+#[test]
+fn test_format_masks_secrets() {
+    assert_eq!("\
+app
+  env
+    LOG_LEVEL=debug
+    DB_PASSWORD=********  (secret db-creds/password)
+    POD_IP=<field status.podIP>
+  env from secret db-creds
+    password=********  (secret db-creds/password)
+  mounts
+    /etc/app  configmap app-config", format(&[container()], false));
+}
+
+#[test]
+fn test_format_reveals_secrets() {
+    let text = format(&[container()], true);
+
+    assert!(text.contains("DB_PASSWORD=hunter2  (secret db-creds/password)"));
+    assert!(!text.contains(MASK));
+}
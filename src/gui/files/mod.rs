@@ -0,0 +1,151 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Clear, Paragraph, Row, Table, TableState};
+
+use crate::kubectl::{self, FoundPod, KubectlRunner, RemoteEntry};
+
+use super::theme::Theme;
+
+/// A browser for the files in one of a pod's containers, to pick what to copy.
+#[derive(Default)]
+pub struct FileBrowser {
+    pub containers: Vec<String>,
+    pub container: usize,
+    /// The directory being shown.
+    pub path: String,
+    pub entries: Vec<RemoteEntry>,
+    pub state: TableState,
+    /// Why the directory couldn't be listed, if it failed.
+    pub error: Option<String>,
+    /// The local path to upload into the current directory, while it's being typed.
+    pub upload_input: Option<String>,
+}
+
+impl FileBrowser {
+    pub fn new(containers: Vec<String>) -> Self {
+        FileBrowser { containers, path: "/".to_string(), ..Default::default() }
+    }
+
+    pub fn container(&self) -> Option<&str> {
+        self.containers.get(self.container).map(String::as_str)
+    }
+
+    /// Lists the current directory, selecting the first entry.
+    pub fn load(&mut self, runner: &dyn KubectlRunner, pod: &FoundPod) {
+        match kubectl::list_remote_dir(runner, pod, self.container(), &self.path) {
+            Ok(entries) => {
+                self.entries = entries;
+                self.error = None;
+            }
+            Err(err) => {
+                self.entries.clear();
+                self.error = Some(err.to_string());
+            }
+        }
+        self.state.select((!self.entries.is_empty()).then_some(0));
+    }
+
+    pub fn selected(&self) -> Option<&RemoteEntry> {
+        self.state.selected().and_then(|selected| self.entries.get(selected))
+    }
+
+    pub fn move_selection(&mut self, offset: isize) {
+        if let Some(selected) = self.state.selected() {
+            let selected = selected.saturating_add_signed(offset).min(self.entries.len().saturating_sub(1));
+            self.state.select(Some(selected));
+        }
+    }
+
+    /// The path of something in the current directory.
+    pub fn child_path(&self, name: &str) -> String {
+        format!("{}/{name}", self.path.trim_end_matches('/'))
+    }
+
+    /// Moves into the selected directory.
+    ///
+    /// # Returns
+    /// `true` if a directory was selected, so it needs loading.
+    pub fn open_selected(&mut self) -> bool {
+        match self.selected() {
+            Some(entry) if entry.is_dir => {
+                self.path = self.child_path(&entry.name);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Moves up to the parent directory.
+    ///
+    /// # Returns
+    /// `true` unless already at the root, so the parent needs loading.
+    pub fn go_up(&mut self) -> bool {
+        if self.path == "/" {
+            return false;
+        }
+
+        self.path = match self.path.trim_end_matches('/').rsplit_once('/') {
+            Some(("", _)) | None => "/".to_string(),
+            Some((parent, _)) => parent.to_string(),
+        };
+        true
+    }
+
+    /// Switches to the next container, starting again from the root.
+    pub fn cycle_container(&mut self) {
+        if !self.containers.is_empty() {
+            self.container = (self.container + 1) % self.containers.len();
+            self.path = "/".to_string();
+        }
+    }
+}
+
+/// Draws the file browser over the given area.
+///
+/// # Arguments
+///
+/// * `browser` - The directory to show, and any upload being typed.
+/// * `progress` - The progress of a copy that's running, if there is one.
+/// * `area` - Where to draw the popup.
+/// * `theme` - The colours to draw with.
+pub fn render(f: &mut Frame, browser: &mut FileBrowser, progress: Option<String>, area: Rect, theme: &Theme) {
+    let container = browser.container().map(|container| format!(" [{container}]")).unwrap_or_default();
+    let block = Block::bordered().fg(theme.foreground)
+        .title_top(Line::from(theme.label(&format!("📂 {}{container} (ESC to close)", browser.path)).into_owned()).left_aligned().bold())
+        .title_bottom(Line::from(theme.label("⏎ open, ⌫ up, [d]ownload, [u]pload, [c]ontainer")).right_aligned())
+        .style(Style::default().bg(theme.background));
+    let inner = block.inner(area);
+
+    f.render_widget(Clear, area); //this clears out the background
+    f.render_widget(block, area);
+
+    let footer = browser.upload_input.as_ref()
+        .map(|input| Line::from(format!("Upload local file: {input}")).fg(theme.foreground))
+        .or_else(|| progress.map(|progress| Line::from(progress).fg(theme.warning)));
+    let [list_area, footer_area] = Layout::vertical([
+        Constraint::Min(0),
+        Constraint::Length(if footer.is_some() { 1 } else { 0 }),
+    ])
+        .areas(inner);
+
+    if let Some(error) = &browser.error {
+        f.render_widget(Paragraph::new(error.as_str()).fg(theme.error), list_area);
+    } else {
+        let rows = browser.entries.iter().map(|entry| {
+            let (icon, name) = if entry.is_dir { ("📁 ", format!("{}/", entry.name)) } else { ("📄 ", entry.name.clone()) };
+            Row::new(vec![Cell::from(format!("{}{name}", theme.label(icon)))]).fg(theme.foreground)
+        });
+        let table = Table::new(rows, [Constraint::Fill(1)])
+            .row_highlight_style(Style::default().bg(theme.selection).bold());
+        f.render_stateful_widget(table, list_area, &mut browser.state);
+    }
+
+    if let Some(footer) = footer {
+        f.render_widget(Paragraph::new(footer), footer_area);
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,51 @@
+use super::*;
+
+fn browser(path: &str) -> FileBrowser {
+    let mut browser = FileBrowser::new(vec!["app".to_string(), "sidecar".to_string()]);
+    browser.path = path.to_string();
+    browser.entries = vec![
+        RemoteEntry { name: "logs".to_string(), is_dir: true },
+        RemoteEntry { name: "heap.hprof".to_string(), is_dir: false },
+    ];
+    browser.state.select(Some(0));
+    browser
+}
+
+#[test]
+fn test_open_selected_directory() {
+    let mut browser = browser("/var");
+
+    assert!(browser.open_selected());
+    assert_eq!("/var/logs", browser.path);
+}
+
+#[test]
+fn test_open_selected_file() {
+    let mut browser = browser("/");
+    browser.move_selection(5);
+
+    assert!(!browser.open_selected());
+    assert_eq!("/", browser.path);
+    assert_eq!("/heap.hprof", browser.child_path(&browser.selected().unwrap().name));
+}
+
+#[test]
+fn test_go_up() {
+    let mut browser = browser("/var/logs");
+
+    assert!(browser.go_up());
+    assert_eq!("/var", browser.path);
+    assert!(browser.go_up());
+    assert_eq!("/", browser.path);
+    assert!(!browser.go_up());
+}
+
+#[test]
+fn test_cycle_container() {
+    let mut browser = browser("/var");
+
+    browser.cycle_container();
+
+    assert_eq!(Some("sidecar"), browser.container());
+    assert_eq!("/", browser.path);
+}
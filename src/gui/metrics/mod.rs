@@ -0,0 +1,269 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::Stylize;
+use ratatui::style::{Color, Style};
+use ratatui::symbols::bar;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Sparkline, Wrap};
+
+use crate::kubectl::{self, ContainerResources, FoundPod, KubectlRunner, ResourceUsage};
+
+use super::theme::Theme;
+
+/// How often usage is sampled while it's on screen. metrics-server only scrapes every 15s by default, so
+/// sampling faster than this just repeats values.
+const METRICS_REFRESH_RATE: Duration = Duration::from_secs(5);
+/// How many samples are kept for each container, which is more than a sparkline will usually have room for.
+const MAX_SAMPLES: usize = 240;
+
+/// Sparkline bars for ASCII mode.
+const ASCII_BAR_SET: bar::Set = bar::Set {
+    full: "#",
+    seven_eighths: "#",
+    three_quarters: "=",
+    five_eighths: "=",
+    half: "-",
+    three_eighths: "-",
+    one_quarter: ".",
+    one_eighth: ".",
+    empty: " ",
+};
+
+/// Usage samples for one container, along with its requests and limits.
+#[derive(Default)]
+pub struct ContainerMetrics {
+    pub resources: ContainerResources,
+    /// CPU samples in millicores, oldest first.
+    pub cpu: VecDeque<u64>,
+    /// Memory samples in bytes, oldest first.
+    pub memory: VecDeque<u64>,
+}
+
+/// CPU and memory usage of a pod's containers, sampled during the session.
+#[derive(Default)]
+pub struct Metrics {
+    pub containers: Vec<ContainerMetrics>,
+    /// Why the last sample couldn't be taken, if it failed.
+    pub error: Option<String>,
+    pub last_sample: Option<Instant>,
+}
+
+impl Metrics {
+    pub fn is_due(&self) -> bool {
+        self.last_sample.is_none_or(|sampled| sampled.elapsed() >= METRICS_REFRESH_RATE)
+    }
+
+    /// Samples the pod's usage, reading its requests and limits the first time round. Failures are kept
+    /// to show in place of the charts, since clusters without metrics-server are common.
+    pub fn sample(&mut self, runner: &dyn KubectlRunner, pod: &FoundPod) {
+        self.last_sample = Some(Instant::now());
+
+        if self.containers.is_empty() {
+            match kubectl::get_container_resources(runner, pod) {
+                Ok(resources) => {
+                    self.containers = resources.into_iter()
+                        .map(|resources| ContainerMetrics { resources, ..Default::default() })
+                        .collect();
+                }
+                Err(err) => {
+                    self.error = Some(err.to_string());
+                    return;
+                }
+            }
+        }
+
+        match kubectl::get_pod_usage(runner, pod) {
+            Ok(usage) => {
+                self.record(&usage);
+                self.error = None;
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+    }
+
+    /// Adds a sample for each container. Containers that aren't in the spec, e.g. ephemeral debug
+    /// containers, are added as they show up.
+    fn record(&mut self, usage: &[ResourceUsage]) {
+        for sample in usage {
+            let index = match self.containers.iter().position(|container| container.resources.name == sample.name) {
+                Some(index) => index,
+                None => {
+                    let resources = ContainerResources { name: sample.name.clone(), ..Default::default() };
+                    self.containers.push(ContainerMetrics { resources, ..Default::default() });
+                    self.containers.len() - 1
+                }
+            };
+
+            let container = &mut self.containers[index];
+            push_sample(&mut container.cpu, sample.cpu_millicores);
+            push_sample(&mut container.memory, sample.memory_bytes);
+        }
+    }
+
+    /// The latest usage of each container as plain text, for copying.
+    pub fn summary(&self) -> String {
+        if let Some(error) = &self.error {
+            return error.clone();
+        }
+
+        self.containers.iter()
+            .map(|container| {
+                let resources = &container.resources;
+                format!(
+                    "{}  CPU {}  memory {}",
+                    resources.name,
+                    usage_summary(container.cpu.back().copied(), resources.cpu_request, resources.cpu_limit, format_cpu),
+                    usage_summary(container.memory.back().copied(), resources.memory_request, resources.memory_limit, format_memory),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn push_sample(samples: &mut VecDeque<u64>, sample: u64) {
+    if samples.len() == MAX_SAMPLES {
+        samples.pop_front();
+    }
+    samples.push_back(sample);
+}
+
+pub fn format_cpu(millicores: u64) -> String {
+    format!("{millicores}m")
+}
+
+pub fn format_memory(bytes: u64) -> String {
+    const KI: u64 = 1024;
+    const MI: u64 = 1024 * KI;
+    const GI: u64 = 1024 * MI;
+
+    match bytes {
+        bytes if bytes >= GI => format!("{:.1}Gi", bytes as f64 / GI as f64),
+        bytes if bytes >= MI => format!("{}Mi", (bytes as f64 / MI as f64).round()),
+        bytes if bytes >= KI => format!("{}Ki", (bytes as f64 / KI as f64).round()),
+        bytes => bytes.to_string(),
+    }
+}
+
+/// Describes the latest sample against the request and limit, e.g. `45Mi / request 64Mi / limit 128Mi (35% of limit)`.
+fn usage_summary(latest: Option<u64>, request: Option<u64>, limit: Option<u64>, format: fn(u64) -> String) -> String {
+    let Some(latest) = latest else {
+        return "no samples yet".to_string();
+    };
+
+    let mut summary = format(latest);
+    if let Some(request) = request {
+        summary += &format!(" / request {}", format(request));
+    }
+    if let Some(limit) = limit {
+        summary += &format!(" / limit {}", format(limit));
+    }
+
+    // The limit is what gets a container throttled or OOMKilled, so it's the more useful comparison.
+    let reference = limit.map(|limit| ("limit", limit)).or(request.map(|request| ("request", request)));
+    if let Some((name, amount)) = reference.filter(|(_, amount)| *amount > 0) {
+        summary += &format!(" ({}% of {name})", latest * 100 / amount);
+    }
+
+    summary
+}
+
+/// Picks a colour for usage: trouble when near the limit, a warning when over the request.
+fn usage_colour(latest: Option<u64>, request: Option<u64>, limit: Option<u64>, theme: &Theme) -> Color {
+    let latest = latest.unwrap_or(0);
+
+    if limit.is_some_and(|limit| latest * 10 >= limit * 9) {
+        theme.error
+    } else if request.is_some_and(|request| latest > request) {
+        theme.warning
+    } else {
+        theme.success
+    }
+}
+
+/// Adds each pod's CPU and memory usage to the end of its line of `kubectl get pods` output.
+pub fn with_usage_columns(pods: &str, usage: &[ResourceUsage]) -> String {
+    let width = pods.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+
+    pods.lines()
+        .map(|line| {
+            let name = line.split_whitespace().next().unwrap_or_default();
+            match usage.iter().find(|pod_usage| pod_usage.name == name) {
+                Some(pod_usage) => format!("{line:<width$}  {:>6}  {:>7}", format_cpu(pod_usage.cpu_millicores), format_memory(pod_usage.memory_bytes)),
+                None => line.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Draws each container's latest usage against its requests and limits, above sparklines of the samples
+/// gathered so far.
+pub fn render(f: &mut Frame, metrics: &Metrics, area: Rect, theme: &Theme) {
+    if let Some(error) = &metrics.error {
+        f.render_widget(Paragraph::new(error.as_str()).fg(theme.error).wrap(Wrap { trim: true }), area);
+        return;
+    }
+
+    if metrics.containers.is_empty() {
+        f.render_widget(Paragraph::new("Waiting for metrics...").fg(theme.muted), area);
+        return;
+    }
+
+    let rows = Layout::vertical(metrics.containers.iter().map(|_| Constraint::Length(5)))
+        .spacing(1)
+        .split(area);
+
+    for (container, row) in metrics.containers.iter().zip(rows.iter()) {
+        let [title_row, charts_row] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(*row);
+        let [cpu_column, memory_column] = Layout::horizontal([Constraint::Percentage(50); 2]).spacing(2).areas(charts_row);
+
+        f.render_widget(Line::from(container.resources.name.as_str()).bold().fg(theme.foreground), title_row);
+
+        render_usage(f, container, Resource::Cpu, cpu_column, theme);
+        render_usage(f, container, Resource::Memory, memory_column, theme);
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Resource {
+    Cpu,
+    Memory,
+}
+
+fn render_usage(f: &mut Frame, container: &ContainerMetrics, resource: Resource, area: Rect, theme: &Theme) {
+    let resources = &container.resources;
+    let (label, samples, request, limit, format): (_, _, _, _, fn(u64) -> String) = match resource {
+        Resource::Cpu => ("CPU", &container.cpu, resources.cpu_request, resources.cpu_limit, format_cpu),
+        Resource::Memory => ("MEM", &container.memory, resources.memory_request, resources.memory_limit, format_memory),
+    };
+
+    let [label_row, chart_area] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+    let latest = samples.back().copied();
+    let colour = usage_colour(latest, request, limit, theme);
+
+    f.render_widget(Line::from(vec![
+        Span::from(format!("{label} ")).bold().fg(theme.foreground),
+        Span::from(usage_summary(latest, request, limit, format)).fg(colour),
+    ]), label_row);
+
+    // Newest samples on the right, as many as fit.
+    let visible_samples: Vec<u64> = samples.iter().skip(samples.len().saturating_sub(chart_area.width as usize)).copied().collect();
+    let peak = visible_samples.iter().copied().max().unwrap_or(0);
+    // Scaled to the limit (or request) so the height means something, unless usage is already past it.
+    let max = limit.or(request).unwrap_or(0).max(peak).max(1);
+
+    let sparkline = Sparkline::default()
+        .data(visible_samples)
+        .max(max)
+        .bar_set(if theme.ascii { ASCII_BAR_SET } else { bar::NINE_LEVELS })
+        .style(Style::default().fg(colour));
+
+    f.render_widget(sparkline, chart_area);
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,74 @@
+use super::*;
+
+fn container(name: &str) -> ContainerMetrics {
+    ContainerMetrics {
+        resources: ContainerResources { name: name.to_string(), ..Default::default() },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_record_adds_samples_by_container() {
+    let mut metrics = Metrics { containers: vec![container("app")], ..Default::default() };
+
+    metrics.record(&[ResourceUsage { name: "app".to_string(), cpu_millicores: 10, memory_bytes: 100 }]);
+    metrics.record(&[
+        ResourceUsage { name: "app".to_string(), cpu_millicores: 20, memory_bytes: 200 },
+        ResourceUsage { name: "debugger".to_string(), cpu_millicores: 1, memory_bytes: 5 },
+    ]);
+
+    assert_eq!(2, metrics.containers.len());
+    assert_eq!(vec![10, 20], Vec::from(metrics.containers[0].cpu.clone()));
+    assert_eq!(vec![100, 200], Vec::from(metrics.containers[0].memory.clone()));
+    assert_eq!("debugger", metrics.containers[1].resources.name);
+    assert_eq!(vec![1], Vec::from(metrics.containers[1].cpu.clone()));
+}
+
+#[test]
+fn test_record_keeps_latest_samples() {
+    let mut metrics = Metrics { containers: vec![container("app")], ..Default::default() };
+
+    for sample in 0..MAX_SAMPLES as u64 + 5 {
+        metrics.record(&[ResourceUsage { name: "app".to_string(), cpu_millicores: sample, memory_bytes: sample }]);
+    }
+
+    let cpu = &metrics.containers[0].cpu;
+    assert_eq!(MAX_SAMPLES, cpu.len());
+    assert_eq!(Some(&5), cpu.front());
+    assert_eq!(Some(&(MAX_SAMPLES as u64 + 4)), cpu.back());
+}
+
+#[test]
+fn test_usage_summary() {
+    assert_eq!("50m / request 100m / limit 200m (25% of limit)", usage_summary(Some(50), Some(100), Some(200), format_cpu));
+    assert_eq!("50m / request 100m (50% of request)", usage_summary(Some(50), Some(100), None, format_cpu));
+    assert_eq!("50m", usage_summary(Some(50), None, None, format_cpu));
+    assert_eq!("no samples yet", usage_summary(None, Some(100), None, format_cpu));
+}
+
+#[test]
+fn test_usage_colour() {
+    let theme = Theme::dark();
+
+    assert_eq!(theme.success, usage_colour(Some(50), Some(100), Some(200), &theme));
+    assert_eq!(theme.warning, usage_colour(Some(150), Some(100), Some(200), &theme));
+    assert_eq!(theme.error, usage_colour(Some(190), Some(100), Some(200), &theme));
+}
+
+#[test]
+fn test_format_memory() {
+    assert_eq!("512", format_memory(512));
+    assert_eq!("2Ki", format_memory(2048));
+    assert_eq!("45Mi", format_memory(45 * 1024 * 1024));
+    assert_eq!("1.5Gi", format_memory(1536 * 1024 * 1024));
+}
+
+#[test]
+fn test_with_usage_columns() {
+    let pods = "pod-a   1/1   Running   0   5m\npod-b   0/1   Pending   0   1m\n";
+    let usage = [ResourceUsage { name: "pod-a".to_string(), cpu_millicores: 12, memory_bytes: 45 * 1024 * 1024 }];
+
+    let result = with_usage_columns(pods, &usage);
+
+    assert_eq!("pod-a   1/1   Running   0   5m     12m     45Mi\npod-b   0/1   Pending   0   1m", result);
+}
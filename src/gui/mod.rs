@@ -1,83 +1,502 @@
 use std::{io};
+use std::path::Path;
+use std::sync::mpsc::Receiver;
 use std::time::{Duration, Instant};
-use crossterm::event::{DisableMouseCapture, Event, KeyCode};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseButton, MouseEvent, MouseEventKind};
 use crossterm::{event, execute};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
 use rand::Rng;
 use ratatui::backend::{Backend, CrosstermBackend};
 use ratatui::text::{Line, Span};
 use ratatui::{Frame, Terminal};
-use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::layout::{Constraint, Layout, Position, Rect};
 use ratatui::prelude::Stylize;
-use ratatui::style::{Color, Style};
+use ratatui::style::{Modifier, Style};
 use color_eyre::eyre::{Result};
-use ratatui::widgets::{Block, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap};
-use tui_piechart::{PieChart, PieSlice};
+use regex::Regex;
+use ratatui::widgets::{Block, Cell, Clear, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, Table, TableState, Tabs, Wrap};
 
-use crate::kubectl::{self, FoundPod, KubectlRunnerAgent, get_pod_status};
-use crate::cli::{self};
+use crate::kubectl::{self, ConfigKind, ContainerEnv, FoundPod, KubeError, KubectlRunner, KubectlRunnerAgent, PodPhase, PodSummary, Transfer, get_pod_status};
+use crate::cli::{self, CopyJob};
+use crate::settings::Settings;
+use clipboard::Copied;
+use debug::DebugForm;
+use files::FileBrowser;
+use diagnostics::Diagnostics;
+use metrics::Metrics;
+use theme::Theme;
+use world::Breakdown;
+
+mod actions;
+mod clipboard;
+mod debug;
+mod files;
+mod diagnostics;
+mod env;
+mod metrics;
+mod theme;
+mod world;
+
+use actions::{InternalAction, ACTIONS};
 
 // FIXME support pods with no deployments??
 // FIXME updater function
 
-pub fn render_action_text<'a>(text: &'a str, action: InternalAction, last_action: &Option<InternalAction>) -> Span<'a> {
+const SIDEBAR_REFRESH_RATE: Duration = Duration::from_secs(5);
+/// How long a status message stays in the tab bar.
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(3);
+
+pub fn render_action_text(text: &str, action: InternalAction, last_action: &Option<InternalAction>, theme: &Theme) -> Span<'static> {
+    let text = theme.label(text).into_owned();
+
     if let Some(last_action) = last_action {
         if *last_action == action {
-            return format!("{text}").blue();
+            return text.fg(theme.highlight);
         }
     }
-    
-    format!("{text}").white()
+
+    text.fg(theme.foreground)
 }
 
-#[derive(PartialEq, Copy, Clone)]
-pub enum InternalAction {
-    FetchLogs,
-    LastLogs,
-    ViewDesc,
-    Purge,
+/// What a pane is showing for its tab's pod.
+#[derive(PartialEq, Copy, Clone, Default)]
+pub enum PaneView {
+    #[default]
+    Logs,
+    PreviousLogs,
+    Describe,
+    Events,
+    Metrics,
+    Diagnostics,
+    Env,
     World,
-    Switch
+    WorldAll,
 }
 
+impl PaneView {
+    fn title(&self) -> &'static str {
+        match self {
+            PaneView::Logs => "📜 logs",
+            PaneView::PreviousLogs => "📖 last logs",
+            PaneView::Describe => "🔎 desc",
+            PaneView::Events => "📅 events",
+            PaneView::Metrics => "📈 usage",
+            PaneView::Diagnostics => "🩺 crash diagnostics",
+            PaneView::Env => "🧾 env",
+            PaneView::World => "🗺️ world",
+            PaneView::WorldAll => "🗺️ world (all)",
+        }
+    }
+
+    /// Logs are read from the bottom up, so they start scrolled to the end.
+    fn follows_tail(&self) -> bool {
+        matches!(self, PaneView::Logs | PaneView::PreviousLogs)
+    }
+
+    fn load(&self, runner: &dyn KubectlRunner, pod: &FoundPod, ascii: bool, reveal_secrets: bool) -> Result<String> {
+        match self {
+            PaneView::Logs => kubectl::get_pod_logs(runner, pod, true, false),
+            PaneView::PreviousLogs => kubectl::get_pod_logs(runner, pod, true, true),
+            PaneView::Describe => kubectl::describe_pod(runner, pod),
+            PaneView::Events => kubectl::get_pod_events(runner, pod),
+            // Shown in the pane rather than closing qk, e.g. when the pod has gone or secrets can't be read.
+            PaneView::Env => Ok(match kubectl::get_pod_env(runner, pod) {
+                Ok(containers) => env::format(&containers, reveal_secrets),
+                Err(err) => format!("Could not read the environment of {}: {err:#}", pod.name),
+            }),
+            // Drawn from the tab's samples rather than loaded.
+            PaneView::Metrics | PaneView::Diagnostics => Ok(String::new()),
+            PaneView::World => {
+                let pods = kubectl::get_pods(runner, pod, ascii)?;
+                // Usage is a bonus, clusters without metrics-server still get the pod list.
+                Ok(match kubectl::get_namespace_usage(runner, &pod.namespace) {
+                    Ok(usage) => metrics::with_usage_columns(&pods, &usage),
+                    Err(_) => pods,
+                })
+            }
+            PaneView::WorldAll => kubectl::get_all(runner, pod),
+        }
+    }
+}
+
+/// How the main area is divided between panes.
+#[derive(PartialEq, Copy, Clone, Default)]
+pub enum PaneLayout {
+    #[default]
+    Single,
+    SideBySide,
+    Stacked,
+}
+
+impl PaneLayout {
+    fn pane_count(&self) -> usize {
+        match self {
+            PaneLayout::Single => 1,
+            PaneLayout::SideBySide | PaneLayout::Stacked => 2,
+        }
+    }
+}
+
+/// A scrollable view of one kind of output for a pod.
 #[derive(Default)]
-struct App {
+struct Pane {
+    pub view: PaneView,
+    pub text: String,
+    pub stale: bool,
     pub vertical_scroll_state: ScrollbarState,
     pub horizontal_scroll_state: ScrollbarState,
     pub vertical_scroll: usize,
     pub horizontal_scroll: usize,
+    pub max_vertical_scroll: usize,
+    pub max_horizontal_scroll: usize,
+    /// The size of the area the text was last drawn in.
+    pub viewport: Rect,
+}
+
+impl Pane {
+    fn new(view: PaneView) -> Self {
+        Pane {
+            view,
+            stale: true,
+            ..Default::default()
+        }
+    }
+
+    /// Switches the pane to a view, which is loaded before the next draw.
+    fn show(&mut self, view: PaneView) {
+        self.view = view;
+        self.stale = true;
+        self.vertical_scroll = 0;
+    }
+
+    /// The lines at least partly visible in the pane when it was last drawn, in full.
+    fn visible_lines(&self, wrap: bool) -> String {
+        let first_row = self.vertical_scroll;
+        let last_row = self.vertical_scroll + self.viewport.height as usize;
+        let mut row = 0;
+
+        self.text.lines()
+            .filter(|line| {
+                let rows = if wrap {
+                    Paragraph::new(*line).wrap(Wrap { trim: false }).line_count(self.viewport.width).max(1)
+                } else {
+                    1
+                };
+                let is_visible = row < last_row && row + rows > first_row;
+                row += rows;
+                is_visible
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The lines matching a regex, or `None` if the regex is invalid or no lines match.
+    fn matching_lines(&self, pattern: &str) -> Option<String> {
+        let regex = Regex::new(pattern).ok()?;
+        let lines: Vec<&str> = self.text.lines().filter(|line| regex.is_match(line)).collect();
+
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+}
+
+/// What the text input popup is asking for.
+#[derive(PartialEq, Copy, Clone)]
+enum InputPurpose {
+    /// A matcher for the pod to switch the current tab to.
+    SwitchPod,
+    /// A matcher for the pod to open in a new tab.
+    NewTab,
+    /// A regex for the lines of the focused pane to copy.
+    CopyMatches,
+    /// A command to run in the pod, for when none of the configured shells will do.
+    ExecCommand,
+    /// Which of the ConfigMaps the pod refers to to edit.
+    EditConfigMap,
+}
+
+/// A pod being watched, along with the panes showing its output.
+#[derive(Default)]
+struct Tab {
+    pub target_pod: FoundPod,
+    pub pod_status: String,
+    pub last_action: Option<InternalAction>,
+    pub panes: [Pane; 2],
+    pub focused_pane: usize,
+    pub metrics: Metrics,
+    pub diagnostics: Diagnostics,
+    /// Whether the pod has been seen crashing, so the diagnostics are only brought up automatically once.
+    pub crash_diagnosed: bool,
+    /// The pods charted alongside the world view.
+    pub world: Vec<PodPhase>,
+}
+
+impl Tab {
+    fn new(target_pod: FoundPod) -> Self {
+        Tab {
+            target_pod,
+            last_action: Some(InternalAction::FetchLogs),
+            panes: [Pane::new(PaneView::Logs), Pane::new(PaneView::Describe)],
+            ..Default::default()
+        }
+    }
+
+    fn pane_mut(&mut self) -> &mut Pane {
+        &mut self.panes[self.focused_pane]
+    }
+}
+
+/// Where the clickable parts of the UI were drawn on the last frame, so mouse events can be mapped back to them.
+#[derive(Default)]
+struct HitMap {
+    /// The bordered area of each visible pane, by pane index.
+    pub panes: Vec<Rect>,
+    /// The rows of the sidebar's pod table, below its header.
+    pub sidebar_rows: Option<Rect>,
+    /// Title bar actions in the order they were drawn, so later ones overlap earlier ones.
+    pub actions: Vec<(Rect, InternalAction)>,
+}
+
+impl HitMap {
+    fn pane_at(&self, position: Position) -> Option<usize> {
+        self.panes.iter().position(|area| area.contains(position))
+    }
+
+    fn action_at(&self, position: Position) -> Option<InternalAction> {
+        self.actions.iter().rev()
+            .find(|(area, _)| area.contains(position))
+            .map(|(_, action)| *action)
+    }
+}
+
+/// Text being selected by dragging the mouse across a pane, in screen cells.
+#[derive(Copy, Clone)]
+struct Selection {
+    pub pane: usize,
+    pub anchor: Position,
+    pub cursor: Position,
+}
+
+impl Selection {
+    /// The start and end of the selection, in reading order.
+    fn bounds(&self) -> (Position, Position) {
+        if (self.anchor.y, self.anchor.x) <= (self.cursor.y, self.cursor.x) {
+            (self.anchor, self.cursor)
+        } else {
+            (self.cursor, self.anchor)
+        }
+    }
+}
+
+#[derive(Default)]
+struct App {
+    pub tabs: Vec<Tab>,
+    pub active_tab: usize,
+    pub layout: PaneLayout,
     pub is_loading: bool,
     pub emoji_frame: usize,
     pub show_pod_deleted_pop_up: bool,
-    pub show_switch_error_text: bool,
-    pub show_pie_chart_for_running_pods: bool,
-    pub new_pod_search_pop_up: bool,
+    /// Why exec'ing into the pod failed, while offering to debug it instead.
+    pub debug_offer: Option<String>,
+    pub debug_pop_up: Option<DebugForm>,
+    pub file_browser: Option<FileBrowser>,
+    /// A file copy running in the background, started from the file browser.
+    pub copy_job: Option<CopyJob>,
+    pub show_input_error: bool,
+    pub input_pop_up: Option<InputPurpose>,
     pub input_text: String,
-    pub target_pod: FoundPod,
+    pub show_help: bool,
+    pub help_state: TableState,
+    pub command_palette_pop_up: bool,
+    pub palette_input: String,
+    pub palette_state: TableState,
+    pub delete_pod_next_tick: bool,
+    pub should_quit: bool,
     pub emoji: String,
-    pub pod_status: String,
-    pub last_action: Option<InternalAction>,
+    pub wrap: bool,
+    pub show_sidebar: bool,
+    pub sidebar_pods: Vec<PodSummary>,
+    pub sidebar_state: TableState,
+    pub last_sidebar_refresh: Option<Instant>,
+    pub settings: Settings,
+    pub theme: Theme,
+    pub status_message: Option<(String, Instant)>,
+    pub hit_map: HitMap,
+    pub selection: Option<Selection>,
+    pub selected_text: String,
+    pub copy_selection: bool,
+    pub world_breakdown: Breakdown,
+    /// Whether the env view shows secret values, rather than masking them.
+    pub reveal_secrets: bool,
+    /// The ConfigMaps the pod refers to, while asking which one to edit.
+    pub config_maps: Vec<String>,
+    /// The tag of a newer release of qk, once the background check has found one.
+    pub newer_release: Option<String>,
+    pub newer_release_receiver: Option<Receiver<String>>,
+}
+
+impl App {
+    fn tab(&self) -> &Tab {
+        &self.tabs[self.active_tab]
+    }
+
+    fn tab_mut(&mut self) -> &mut Tab {
+        &mut self.tabs[self.active_tab]
+    }
+
+    /// Points the current tab at a new pod, ready for its logs to be fetched.
+    fn switch_to_pod(&mut self, pod: FoundPod) {
+        let tab = self.tab_mut();
+        tab.target_pod = pod;
+        tab.metrics = Metrics::default();
+        tab.diagnostics = Diagnostics::default();
+        tab.crash_diagnosed = false;
+        tab.last_action = Some(InternalAction::FetchLogs);
+        tab.panes[0].show(PaneView::Logs);
+        tab.panes[1].stale = true;
+        self.is_loading = true;
+    }
+
+    /// Opens a new tab for the pod and makes it the current tab.
+    fn open_tab(&mut self, pod: FoundPod) {
+        self.tabs.push(Tab::new(pod));
+        self.active_tab = self.tabs.len() - 1;
+        self.is_loading = true;
+    }
+
+    /// Closes the current tab, unless it is the only one left.
+    fn close_tab(&mut self) {
+        if self.tabs.len() > 1 {
+            self.tabs.remove(self.active_tab);
+            self.active_tab = self.active_tab.min(self.tabs.len() - 1);
+        }
+    }
+
+    fn cycle_tab(&mut self, offset: isize) {
+        self.active_tab = (self.active_tab as isize + offset).rem_euclid(self.tabs.len() as isize) as usize;
+    }
+
+    /// Toggles between a single pane and the given split, refreshing the second pane as it appears.
+    fn toggle_split(&mut self, layout: PaneLayout) {
+        if self.layout == layout {
+            self.layout = PaneLayout::Single;
+            for tab in self.tabs.iter_mut() {
+                tab.focused_pane = 0;
+            }
+        } else {
+            self.layout = layout;
+            self.tab_mut().panes[1].stale = true;
+        }
+    }
+
+    fn focus_next_pane(&mut self) {
+        let pane_count = self.layout.pane_count();
+        let tab = self.tab_mut();
+        tab.focused_pane = (tab.focused_pane + 1) % pane_count;
+    }
+
+    /// Refreshes the sidebar on the next tick, e.g. after the namespace may have changed.
+    fn reset_sidebar(&mut self) {
+        self.last_sidebar_refresh = None;
+        self.sidebar_state.select(None);
+    }
+
+    fn select_sidebar_pod(&mut self, offset: isize) {
+        if self.sidebar_pods.is_empty() {
+            return;
+        }
+
+        let selected = self.sidebar_state.selected().unwrap_or(0);
+        let selected = selected.saturating_add_signed(offset).min(self.sidebar_pods.len() - 1);
+        self.sidebar_state.select(Some(selected));
+    }
+
+    /// Copies text to the clipboard, then lets the user know where it went.
+    fn copy(&mut self, what: &str, text: &str) {
+        let message = match clipboard::copy(text, self.settings.clipboard) {
+            Ok(Copied::Clipboard) => format!("📋 Copied {what}"),
+            Ok(Copied::File(path)) => format!("📋 Copied {what} to {}", path.display()),
+            Err(err) => format!("❌ Couldn't copy {what}: {err}"),
+        };
+        self.show_status(&message);
+    }
+
+    fn show_status(&mut self, message: &str) {
+        self.status_message = Some((self.theme.label(message).into_owned(), Instant::now()));
+    }
+
+    /// Lets the user know if a command run outside the TUI failed.
+    fn show_result(&mut self, result: Result<()>) {
+        if let Err(err) = result {
+            self.show_status(&format!("❌ {err}"));
+        }
+    }
+
+    /// Starts copying files to or from the current pod, unless a copy is already running.
+    fn start_copy(&mut self, runner: &dyn KubectlRunner, container: Option<&str>, transfer: Transfer) {
+        if self.copy_job.is_some() {
+            self.show_status("❌ Wait for the current copy to finish");
+            return;
+        }
+
+        match CopyJob::start(runner, &self.tab().target_pod, container, transfer) {
+            Ok(job) => self.copy_job = Some(job),
+            Err(err) => self.show_status(&format!("❌ {err}")),
+        }
+    }
+
+    fn open_input_pop_up(&mut self, purpose: InputPurpose) {
+        self.input_pop_up = Some(purpose);
+        self.show_input_error = false;
+        self.input_text.clear();
+    }
+
+    /// Scrolls the pane under the mouse, rather than the focused one.
+    fn scroll_pane_at(&mut self, position: Position, rows: isize, columns: isize) {
+        if let Some(index) = self.hit_map.pane_at(position) {
+            self.selection = None;
+            let pane = &mut self.tab_mut().panes[index];
+            pane.vertical_scroll = pane.vertical_scroll.saturating_add_signed(rows).min(pane.max_vertical_scroll);
+            pane.horizontal_scroll = pane.horizontal_scroll.saturating_add_signed(columns).min(pane.max_horizontal_scroll);
+        }
+    }
 }
 
 
-pub fn gui(target: FoundPod) -> Result<()> {
+/// Runs the TUI for a pod until the user quits.
+///
+/// # Arguments
+///
+/// * `target` - The pod to show in the first tab.
+/// * `settings` - The user's settings.
+/// * `newer_release` - Where a background check sends a newer release's tag, to be mentioned in the tab bar.
+pub fn gui(target: FoundPod, settings: &Settings, newer_release: Option<Receiver<String>>) -> Result<()> {
+    // Checked before taking over the terminal, so a bad theme is reported like any other error.
+    let theme = Theme::from_settings(settings)?;
 
     // Find pod(s) based on supplied matcher in --all-namespaces
     // SSH can only be one, present list to user? or default to first
-    // (Stretch) If multiple for log, then use tabs to open?
     // Build command, e.g. log --all-namespaces (no -n)
     // setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, DisableMouseCapture)?;
+    if settings.mouse {
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    } else {
+        execute!(stdout, EnterAlternateScreen, DisableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // create app and run it
     let tick_rate = Duration::from_millis(250);
-    let mut app = App::default();
-    app.last_action = Some(InternalAction::FetchLogs);
-    app.target_pod = target;
+    let app = App {
+        tabs: vec![Tab::new(target)],
+        wrap: true,
+        settings: settings.clone(),
+        theme,
+        newer_release_receiver: newer_release,
+        ..Default::default()
+    };
     let res = run_app(&mut terminal, app, tick_rate);
 
     // restore terminal
@@ -102,12 +521,7 @@ fn run_app<B: Backend>(
     tick_rate: Duration
 ) -> Result<String> where <B as Backend>::Error: Send, <B as Backend>::Error: Sync, <B as Backend>::Error: 'static, <B as Backend>::Error: 'static {
     let mut last_tick = Instant::now();
-    let mut fetch_new_logs = false;
-    let mut fetch_prev_container_logs = false;
-    let mut delete_pod_next_tick = false;
-    let mut reset_scroll = true;
     let runner = KubectlRunnerAgent;
-    let mut text = kubectl::get_pod_logs(&runner, &app.target_pod, true, false)?;
     let icons = ["🐝", "🦀", "🐋", "🐧", "🦕", "🦐", "🐬", "🦞", "🤖", "🐤", "🪿"];
     app.emoji_frame = rand::rng().random_range(0..icons.len());
     app.emoji = icons[app.emoji_frame].to_string();
@@ -118,67 +532,261 @@ fn run_app<B: Backend>(
     let index = rng.random_range(0..icons.len());
     let emoji = icons[index];
     app.emoji = emoji.to_string();
+    if app.theme.ascii {
+        app.emoji = "qk".to_string();
+    }
 
     loop {
-        app.pod_status = get_pod_status(&runner, &app.target_pod)?;
+        app.tab_mut().pod_status = get_pod_status(&runner, &app.tab().target_pod, app.theme.ascii)?;
 
-        if reset_scroll {
-            if text.lines().count() > 0 {
-                app.vertical_scroll = text.lines().count() - 1;
+        let tab = app.tab_mut();
+        if !tab.crash_diagnosed && kubectl::is_crash_status(&tab.pod_status) {
+            tab.crash_diagnosed = true;
+            tab.pane_mut().show(PaneView::Diagnostics);
+            tab.last_action = Some(InternalAction::Diagnose);
+        }
+
+        if app.show_sidebar && app.last_sidebar_refresh.is_none_or(|refreshed| refreshed.elapsed() >= SIDEBAR_REFRESH_RATE) {
+            // Keep showing the previous list if kubectl hiccups, it'll be retried next refresh.
+            if let Ok(pods) = kubectl::list_pods(&runner, &app.tab().target_pod.namespace) {
+                let selected = match app.sidebar_state.selected() {
+                    Some(selected) => selected.min(pods.len().saturating_sub(1)),
+                    None => pods.iter().position(|pod| pod.name == app.tab().target_pod.name).unwrap_or(0),
+                };
+                app.sidebar_state.select(Some(selected));
+                app.sidebar_pods = pods;
+            }
+            app.last_sidebar_refresh = Some(Instant::now());
+        }
+
+        let pane_count = app.layout.pane_count();
+        let ascii = app.theme.ascii;
+        let world_breakdown = app.world_breakdown;
+        let reveal_secrets = app.reveal_secrets;
+        let tab = app.tab_mut();
+        for pane in tab.panes.iter_mut().take(pane_count).filter(|pane| pane.stale) {
+            pane.text = pane.view.load(&runner, &tab.target_pod, ascii, reveal_secrets)?;
+            pane.stale = false;
+            if pane.view == PaneView::World {
+                let namespace = (!world_breakdown.all_namespaces()).then_some(tab.target_pod.namespace.as_str());
+                // Like usage, the chart is a bonus on top of the pod list.
+                tab.world = kubectl::get_pod_phases(&runner, namespace).unwrap_or_default();
+            }
+            if pane.view.follows_tail() {
+                // Clamped to the last page of content when drawn.
+                pane.vertical_scroll = usize::MAX;
             }
-            reset_scroll = false;
         }
 
-        if fetch_prev_container_logs {
-            text = kubectl::get_pod_logs(&runner, &app.target_pod, true, true)?;
-            fetch_prev_container_logs = false;
-            reset_scroll = true;
+        let tab = app.tab_mut();
+        let showing_metrics = tab.panes.iter().take(pane_count).any(|pane| pane.view == PaneView::Metrics);
+        if showing_metrics {
+            if tab.metrics.is_due() {
+                tab.metrics.sample(&runner, &tab.target_pod);
+            }
+            // Kept as text so it can be copied like any other pane.
+            for pane in tab.panes.iter_mut().filter(|pane| pane.view == PaneView::Metrics) {
+                pane.text = tab.metrics.summary();
+            }
         }
 
-        if fetch_new_logs {
-            text = kubectl::get_pod_logs(&runner, &app.target_pod, true, false)?;
-            fetch_new_logs = false;
-            reset_scroll = true;
+        let showing_diagnostics = tab.panes.iter().take(pane_count).any(|pane| pane.view == PaneView::Diagnostics);
+        if showing_diagnostics {
+            if tab.diagnostics.is_due() {
+                tab.diagnostics.sample(&runner, &tab.target_pod);
+            }
+            let report = tab.diagnostics.report(Instant::now());
+            for pane in tab.panes.iter_mut().filter(|pane| pane.view == PaneView::Diagnostics) {
+                pane.text = report.clone();
+            }
         }
 
-        if delete_pod_next_tick {
-            text = text + "\nDeleted :(. Press 'q' to quit.";
+        if app.delete_pod_next_tick {
+            let pane = app.tab_mut().pane_mut();
+            pane.text += "\nDeleted :(. Press 'q' to quit.";
+            pane.vertical_scroll = usize::MAX;
             app.show_pod_deleted_pop_up = true;
-            kubectl::delete_pod(&runner, &app.target_pod).unwrap();
-            delete_pod_next_tick = false;
-            reset_scroll = true;
+            kubectl::delete_pod(&runner, &app.tab().target_pod).unwrap();
+            app.delete_pod_next_tick = false;
         }
 
-        terminal.draw(|f| ui(f, &mut app, &text))?;
+        if let Some(newer_release) = app.newer_release_receiver.as_ref().and_then(|receiver| receiver.try_recv().ok()) {
+            app.newer_release = Some(newer_release);
+        }
+
+        if let Some(job) = &mut app.copy_job {
+            match job.poll(&runner) {
+                None => {
+                    let progress = job.progress();
+                    app.show_status(&format!("⏳ {progress}"));
+                }
+                Some(result) => {
+                    let progress = job.progress();
+                    app.copy_job = None;
+                    match result {
+                        Ok(()) => app.show_status(&format!("✅ Copied {progress}")),
+                        Err(err) => app.show_status(&format!("❌ {err}")),
+                    }
+                    // Show what was uploaded.
+                    if let Some(browser) = &mut app.file_browser {
+                        browser.load(&runner, &app.tabs[app.active_tab].target_pod);
+                    }
+                }
+            }
+        }
+
+        terminal.draw(|f| ui(f, &mut app))?;
+
+        if app.copy_selection {
+            let selected_text = app.selected_text.clone();
+            app.copy("selection", &selected_text);
+            app.copy_selection = false;
+        }
 
         let timeout = tick_rate.saturating_sub(last_tick.elapsed());
         if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if app.new_pod_search_pop_up {
+            let event = event::read()?;
+            if let Event::Mouse(mouse) = event {
+                handle_mouse(terminal, &mut app, &runner, mouse);
+            }
+            if let Event::Key(key) = event {
+                app.selection = None;
+                if let Some(browser) = &mut app.file_browser {
+                    let pod = &app.tabs[app.active_tab].target_pod;
+                    if let Some(input) = &mut browser.upload_input {
+                        match key.code {
+                            KeyCode::Esc => browser.upload_input = None,
+                            KeyCode::Char(to_insert) => input.push(to_insert),
+                            KeyCode::Backspace => {
+                                input.pop();
+                            }
+                            KeyCode::Enter => {
+                                let local = input.trim().to_string();
+                                let name = Path::new(&local).file_name().map(|name| name.to_string_lossy().into_owned());
+                                browser.upload_input = None;
+                                if let Some(name) = name {
+                                    let transfer = Transfer::Upload { remote: browser.child_path(&name), local };
+                                    let container = browser.container().map(str::to_string);
+                                    app.start_copy(&runner, container.as_deref(), transfer);
+                                }
+                            }
+                            _ => {}
+                        }
+                    } else {
+                        match key.code {
+                            KeyCode::Esc => app.file_browser = None,
+                            KeyCode::Up | KeyCode::Char('k') => browser.move_selection(-1),
+                            KeyCode::Down | KeyCode::Char('j') => browser.move_selection(1),
+                            KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') if browser.go_up() => browser.load(&runner, pod),
+                            KeyCode::Char('c') => {
+                                browser.cycle_container();
+                                browser.load(&runner, pod);
+                            }
+                            KeyCode::Char('u') => browser.upload_input = Some(String::new()),
+                            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('d') => {
+                                if key.code != KeyCode::Char('d') && browser.open_selected() {
+                                    browser.load(&runner, pod);
+                                } else if let Some(entry) = browser.selected() {
+                                    // Downloaded into the directory qk was started from, but never over something already there.
+                                    let local = format!("./{}", entry.name);
+                                    if Path::new(&local).exists() {
+                                        app.show_status(&format!("❌ {local} already exists, move it out of the way to download it"));
+                                    } else {
+                                        let transfer = Transfer::Download { remote: browser.child_path(&entry.name), local };
+                                        let container = browser.container().map(str::to_string);
+                                        app.start_copy(&runner, container.as_deref(), transfer);
+                                    }
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                } else if let Some(form) = &mut app.debug_pop_up {
+                    match key.code {
+                        KeyCode::Esc => app.debug_pop_up = None,
+                        KeyCode::Up | KeyCode::Char('k') | KeyCode::BackTab => form.move_focus(-1),
+                        KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => form.move_focus(1),
+                        KeyCode::Left | KeyCode::Char('h') => form.cycle(-1),
+                        KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(' ') => form.cycle(1),
+                        KeyCode::Enter => {
+                            let options = form.options();
+                            app.debug_pop_up = None;
+                            let result = run_outside_tui(terminal, app.settings.mouse, || kubectl::debug_pod(&runner, &app.tab().target_pod, &options));
+                            app.show_result(result);
+                        }
+                        _ => {}
+                    }
+                } else if app.debug_offer.is_some() {
+                    app.debug_offer = None;
+                    if matches!(key.code, KeyCode::Char('y') | KeyCode::Enter) {
+                        perform_action(terminal, &mut app, &runner, InternalAction::Debug);
+                    }
+                } else if let Some(purpose) = app.input_pop_up {
                     match key.code {
                         KeyCode::Char(to_insert) => {
                             app.input_text.insert(app.input_text.len(), to_insert);
                         },
                         KeyCode::Esc => {
-                            app.new_pod_search_pop_up = false;
+                            app.input_pop_up = None;
+                            app.show_input_error = false;
                             app.input_text.clear();
                         }
+                        KeyCode::Enter if purpose == InputPurpose::ExecCommand => {
+                            if let Some(command) = kubectl::split_command(&app.input_text) {
+                                let command: Vec<&str> = command.iter().map(String::as_str).collect();
+                                let result = run_outside_tui(terminal, app.settings.mouse, || kubectl::exec_command(&runner, &app.tab().target_pod, &command));
+                                app.show_result(result);
+                                app.input_text.clear();
+                                app.show_input_error = false;
+                                app.input_pop_up = None;
+                            } else {
+                                app.show_input_error = true;
+                            }
+                        }
+                        KeyCode::Enter if purpose == InputPurpose::EditConfigMap => {
+                            let name = app.input_text.trim().to_string();
+                            if app.config_maps.contains(&name) {
+                                app.input_text.clear();
+                                app.show_input_error = false;
+                                app.input_pop_up = None;
+                                let result = run_outside_tui(terminal, app.settings.mouse, || kubectl::edit_config_map(&runner, &app.tab().target_pod, &name));
+                                app.show_result(result);
+                            } else {
+                                app.input_text.clear();
+                                app.show_input_error = true;
+                            }
+                        }
+                        KeyCode::Enter if purpose == InputPurpose::CopyMatches => {
+                            match app.tab().panes[app.tab().focused_pane].matching_lines(&app.input_text) {
+                                Some(lines) => {
+                                    app.copy("matching lines", &lines);
+                                    app.input_text.clear();
+                                    app.show_input_error = false;
+                                    app.input_pop_up = None;
+                                },
+                                None => {
+                                    app.input_text.clear();
+                                    app.show_input_error = true;
+                                }
+                            }
+                        }
                         KeyCode::Enter => {
                             let matching_pod_result = kubectl::find_matching_pod(&runner, app.input_text.as_str());
                             match matching_pod_result {
                                 Ok(matching_pod) => {
-                                    app.target_pod = matching_pod;
-                                    fetch_new_logs = true;
-                                    app.is_loading = true;
-                                    app.last_action = Some(InternalAction::FetchLogs);
-                                    app.vertical_scroll = 0;
+                                    if purpose == InputPurpose::NewTab {
+                                        app.open_tab(matching_pod);
+                                    } else {
+                                        app.switch_to_pod(matching_pod);
+                                    }
+                                    app.reset_sidebar();
                                     app.input_text.clear();
-                                    app.show_switch_error_text = false;
-                                    app.new_pod_search_pop_up = false;
+                                    app.show_input_error = false;
+                                    app.input_pop_up = None;
                                 },
                                 Err(_) => {
-                                    app.input_text.clear(); 
-                                    app.show_switch_error_text = true;
+                                    app.input_text.clear();
+                                    app.show_input_error = true;
                                 }
                             }
                         }
@@ -187,91 +795,69 @@ fn run_app<B: Backend>(
                         }
                         _ => {}
                     }
-                } else {
+                } else if app.command_palette_pop_up {
                     match key.code {
-                        KeyCode::Char('q') => return Ok("quit".to_string()),
-                        KeyCode::Char('s') => {
-                            app.new_pod_search_pop_up = true;
-                            app.last_action = Some(InternalAction::Switch);
+                        KeyCode::Esc => {
+                            app.command_palette_pop_up = false;
+                            app.palette_input.clear();
                         }
-                        KeyCode::Char('f') => {
-                            fetch_new_logs = true;
-                            
-                            app.last_action = Some(InternalAction::FetchLogs);
-                        },
-                        KeyCode::Char('p') => {
-                            delete_pod_next_tick = true;
-                            app.last_action = Some(InternalAction::Purge);
-                        },
-                        KeyCode::Char('d') => {
-                            text = kubectl::describe_pod(&runner, &app.target_pod).unwrap();
-                            app.vertical_scroll = 0;
-                            app.last_action = Some(InternalAction::ViewDesc);
-                        },
-                        KeyCode::Char('E') => {
-                            terminal.clear().unwrap();
-                            kubectl::edit_deployment(&runner, &app.target_pod).unwrap();
-                            terminal.clear().unwrap();
-                        },
-                        KeyCode::Char('w') => {
-                            text = kubectl::get_pods(&runner, &app.target_pod).unwrap();
-                            app.vertical_scroll = 0;
-                            app.show_pie_chart_for_running_pods = true;
-                            app.last_action = Some(InternalAction::World);
-                        },
-                        KeyCode::Char('W') => {
-                            text = kubectl::get_all(&runner, &app.target_pod).unwrap();
-                            app.vertical_scroll = 0;
-                            app.show_pie_chart_for_running_pods = false;
-                            app.last_action = Some(InternalAction::World);
-                        },
-                        KeyCode::Char('e') => {
-                            terminal.clear().unwrap();
-                            kubectl::exec_into_pod(&runner, &app.target_pod).unwrap();
-                            terminal.clear().unwrap();
-                        },
-                        KeyCode::Char('b') => {
-                            terminal.clear().unwrap();
-                            kubectl::debug_pod(&runner, &app.target_pod).unwrap();
-                            terminal.clear().unwrap();
-                        },
-                        KeyCode::Char('v') => {
-                            terminal.clear().unwrap();
-                            cli::open_in_vim(&runner, &app.target_pod).unwrap();
-                            terminal.clear().unwrap();
-                        },
-                        KeyCode::Char('l') => {
-                            fetch_prev_container_logs = true;
-                            app.last_action = Some(InternalAction::LastLogs);
-                        },
-                        KeyCode::Char('j') | KeyCode::Down => {
-                            if app.vertical_scroll + 1 < text.lines().count() {
-                                app.vertical_scroll = app.vertical_scroll.saturating_add(1);
-                                app.vertical_scroll_state =
-                                    app.vertical_scroll_state.position(app.vertical_scroll);
+                        KeyCode::Enter => {
+                            let results = actions::search(&app.palette_input);
+                            let selected = app.palette_state.selected().and_then(|selected| results.get(selected));
+                            if let Some(spec) = selected {
+                                app.command_palette_pop_up = false;
+                                app.palette_input.clear();
+                                perform_action(terminal, &mut app, &runner, spec.action);
                             }
                         }
-                        KeyCode::PageDown => {
-                            if app.vertical_scroll + 20 < text.lines().count() {
-                                app.vertical_scroll = app.vertical_scroll.saturating_add(20);
-                                app.vertical_scroll_state =
-                                    app.vertical_scroll_state.position(app.vertical_scroll);
-                            }
+                        KeyCode::Down => {
+                            let result_count = actions::search(&app.palette_input).len();
+                            let selected = app.palette_state.selected().map_or(0, |selected| selected + 1);
+                            app.palette_state.select(Some(selected.min(result_count.saturating_sub(1))));
                         }
-                        KeyCode::Char('k') | KeyCode::Up => {
-                            app.vertical_scroll = app.vertical_scroll.saturating_sub(1);
-                            app.vertical_scroll_state =
-                                app.vertical_scroll_state.position(app.vertical_scroll);
+                        KeyCode::Up => {
+                            let selected = app.palette_state.selected().unwrap_or(0);
+                            app.palette_state.select(Some(selected.saturating_sub(1)));
                         }
-                        KeyCode::PageUp => {
-                            app.vertical_scroll = app.vertical_scroll.saturating_sub(20);
-                            app.vertical_scroll_state =
-                                app.vertical_scroll_state.position(app.vertical_scroll);
+                        KeyCode::Char(to_insert) => {
+                            app.palette_input.push(to_insert);
+                            app.palette_state.select(Some(0));
+                        }
+                        KeyCode::Backspace => {
+                            app.palette_input.pop();
+                            app.palette_state.select(Some(0));
                         }
                         _ => {}
                     }
+                } else if app.show_help {
+                    match key.code {
+                        KeyCode::Char('j') | KeyCode::Down => {
+                            let offset = app.help_state.offset_mut();
+                            *offset = (*offset + 1).min(ACTIONS.len() - 1);
+                        }
+                        KeyCode::Char('k') | KeyCode::Up => {
+                            let offset = app.help_state.offset_mut();
+                            *offset = offset.saturating_sub(1);
+                        }
+                        _ => app.show_help = false,
+                    }
+                } else {
+                    match key.code {
+                        KeyCode::Down if app.show_sidebar => perform_action(terminal, &mut app, &runner, InternalAction::SelectNextPod),
+                        KeyCode::Up if app.show_sidebar => perform_action(terminal, &mut app, &runner, InternalAction::SelectPreviousPod),
+                        KeyCode::Enter if app.show_sidebar => perform_action(terminal, &mut app, &runner, InternalAction::SwitchToSelectedPod),
+                        code => {
+                            if let Some(action) = actions::action_for_key(code) {
+                                perform_action(terminal, &mut app, &runner, action);
+                            }
+                        }
+                    }
                 }
             }
+
+            if app.should_quit {
+                return Ok("quit".to_string());
+            }
         }
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
@@ -279,16 +865,330 @@ fn run_app<B: Backend>(
     }
 }
 
-fn ui(f: &mut Frame, app: &mut App, text: &str) {
-    let size = f.area();
-    let pod_name = &app.target_pod.name;
-    let pod_deployment = &app.target_pod.deployment;
-    let pod_ns = &app.target_pod.namespace;
-    let last_action = &app.last_action;
+/// Carries out an action from the registry, whether it came from a key binding or the command palette.
+fn perform_action<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    runner: &dyn KubectlRunner,
+    action: InternalAction,
+) where <B as Backend>::Error: Send, <B as Backend>::Error: Sync, <B as Backend>::Error: 'static {
+    match action {
+        InternalAction::Quit => app.should_quit = true,
+        InternalAction::Switch => {
+            app.open_input_pop_up(InputPurpose::SwitchPod);
+            app.tab_mut().last_action = Some(InternalAction::Switch);
+        }
+        InternalAction::NewTab => {
+            app.open_input_pop_up(InputPurpose::NewTab);
+        }
+        InternalAction::CloseTab => {
+            app.close_tab();
+            app.reset_sidebar();
+        }
+        InternalAction::CloseOtherTabs => {
+            let tab = app.tabs.swap_remove(app.active_tab);
+            app.tabs = vec![tab];
+            app.active_tab = 0;
+        }
+        InternalAction::NextTab => {
+            app.cycle_tab(1);
+            app.reset_sidebar();
+        }
+        InternalAction::PreviousTab => {
+            app.cycle_tab(-1);
+            app.reset_sidebar();
+        }
+        InternalAction::SplitSideBySide => app.toggle_split(PaneLayout::SideBySide),
+        InternalAction::SplitStacked => app.toggle_split(PaneLayout::Stacked),
+        InternalAction::SinglePane => {
+            if app.layout != PaneLayout::Single {
+                app.toggle_split(app.layout);
+            }
+        }
+        InternalAction::FocusOtherPane => app.focus_next_pane(),
+        InternalAction::CopyPodName => {
+            let name = app.tab().target_pod.name.clone();
+            app.copy("pod name", &name);
+        }
+        InternalAction::CopyPodPath => {
+            let pod = &app.tab().target_pod;
+            let path = format!("{}/{}", pod.namespace, pod.name);
+            app.copy("namespace/name", &path);
+        }
+        InternalAction::CopyVisible => {
+            let lines = app.tab().panes[app.tab().focused_pane].visible_lines(app.wrap);
+            app.copy("visible lines", &lines);
+        }
+        InternalAction::CopyBuffer => {
+            let text = app.tab().panes[app.tab().focused_pane].text.clone();
+            app.copy("whole buffer", &text);
+        }
+        InternalAction::CopyMatches => app.open_input_pop_up(InputPurpose::CopyMatches),
+        InternalAction::RefreshPanes => {
+            for pane in app.tab_mut().panes.iter_mut() {
+                pane.stale = true;
+            }
+        }
+        InternalAction::FetchLogs => {
+            let tab = app.tab_mut();
+            tab.pane_mut().show(PaneView::Logs);
+            tab.last_action = Some(InternalAction::FetchLogs);
+        },
+        InternalAction::Metrics => {
+            let tab = app.tab_mut();
+            tab.pane_mut().show(PaneView::Metrics);
+            tab.last_action = Some(InternalAction::Metrics);
+        },
+        InternalAction::Diagnose => {
+            let tab = app.tab_mut();
+            tab.pane_mut().show(PaneView::Diagnostics);
+            // Refreshed straight away rather than on the next sample.
+            tab.diagnostics.last_sample = None;
+            tab.last_action = Some(InternalAction::Diagnose);
+        },
+        InternalAction::Purge => {
+            app.delete_pod_next_tick = true;
+            app.tab_mut().last_action = Some(InternalAction::Purge);
+        },
+        InternalAction::ViewDesc => {
+            let tab = app.tab_mut();
+            tab.pane_mut().show(PaneView::Describe);
+            tab.last_action = Some(InternalAction::ViewDesc);
+        },
+        InternalAction::Events => {
+            let tab = app.tab_mut();
+            tab.pane_mut().show(PaneView::Events);
+            tab.last_action = Some(InternalAction::Events);
+        },
+        InternalAction::Edit => {
+            let result = run_outside_tui(terminal, app.settings.mouse, || kubectl::edit_deployment(runner, &app.tab().target_pod));
+            app.show_result(result);
+        },
+        InternalAction::Env => {
+            let tab = app.tab_mut();
+            tab.pane_mut().show(PaneView::Env);
+            tab.last_action = Some(InternalAction::Env);
+        },
+        InternalAction::RevealSecrets => {
+            app.reveal_secrets = !app.reveal_secrets;
+            for pane in app.tab_mut().panes.iter_mut().filter(|pane| pane.view == PaneView::Env) {
+                pane.stale = true;
+            }
+        },
+        InternalAction::EditConfigMap => {
+            let config_maps = kubectl::get_pod_env(runner, &app.tab().target_pod).map(|containers| {
+                let mut config_maps: Vec<String> = containers.iter()
+                    .flat_map(ContainerEnv::references)
+                    .filter(|(kind, _)| *kind == ConfigKind::ConfigMap)
+                    .map(|(_, name)| name)
+                    .collect();
+                config_maps.sort();
+                config_maps.dedup();
+                config_maps
+            });
+            match config_maps.as_deref() {
+                Ok([]) => app.show_status("❌ The pod doesn't use any ConfigMaps"),
+                Ok([name]) => {
+                    let result = run_outside_tui(terminal, app.settings.mouse, || kubectl::edit_config_map(runner, &app.tab().target_pod, name));
+                    app.show_result(result);
+                }
+                Ok(_) => {
+                    app.open_input_pop_up(InputPurpose::EditConfigMap);
+                    app.config_maps = config_maps.unwrap_or_default();
+                }
+                Err(err) => app.show_status(&format!("❌ {err}")),
+            }
+        },
+        InternalAction::World => {
+            let tab = app.tab_mut();
+            tab.pane_mut().show(PaneView::World);
+            tab.last_action = Some(InternalAction::World);
+        },
+        InternalAction::WorldAll => {
+            let tab = app.tab_mut();
+            tab.pane_mut().show(PaneView::WorldAll);
+            tab.last_action = Some(InternalAction::World);
+        },
+        InternalAction::WorldBreakdown => {
+            app.world_breakdown = app.world_breakdown.next();
+            for pane in app.tab_mut().panes.iter_mut().filter(|pane| pane.view == PaneView::World) {
+                pane.stale = true;
+            }
+        },
+        InternalAction::Exec => {
+            let result = run_outside_tui(terminal, app.settings.mouse, || kubectl::exec_into_pod(runner, &app.tab().target_pod, &app.settings.shells));
+            match result {
+                // Distroless images have no shell, but an ephemeral debug container brings its own.
+                Err(err) if matches!(err.downcast_ref::<KubeError>(), Some(KubeError::NoShellFound(..))) => {
+                    app.debug_offer = Some(err.to_string());
+                }
+                result => app.show_result(result),
+            }
+        },
+        InternalAction::ExecCommand => app.open_input_pop_up(InputPurpose::ExecCommand),
+        InternalAction::CopyFiles => {
+            match kubectl::get_container_names(runner, &app.tab().target_pod) {
+                Ok(containers) => {
+                    let mut browser = FileBrowser::new(containers);
+                    browser.load(runner, &app.tab().target_pod);
+                    app.file_browser = Some(browser);
+                }
+                Err(err) => app.show_status(&format!("❌ {err}")),
+            }
+        },
+        InternalAction::Debug => {
+            match kubectl::get_container_names(runner, &app.tab().target_pod) {
+                Ok(containers) => app.debug_pop_up = Some(DebugForm::new(app.settings.debug_images.clone(), containers)),
+                Err(err) => app.show_status(&format!("❌ {err}")),
+            }
+        },
+        InternalAction::Vim => {
+            let result = run_outside_tui(terminal, app.settings.mouse, || cli::open_in_vim(runner, &app.tab().target_pod));
+            app.show_result(result);
+        },
+        InternalAction::LastLogs => {
+            let tab = app.tab_mut();
+            tab.pane_mut().show(PaneView::PreviousLogs);
+            tab.last_action = Some(InternalAction::LastLogs);
+        },
+        InternalAction::ToggleSidebar => {
+            app.show_sidebar = !app.show_sidebar;
+            app.last_sidebar_refresh = None;
+        },
+        InternalAction::SelectNextPod => app.select_sidebar_pod(1),
+        InternalAction::SelectPreviousPod => app.select_sidebar_pod(-1),
+        InternalAction::SwitchToSelectedPod => {
+            let selected_pod = app.sidebar_state.selected().and_then(|selected| app.sidebar_pods.get(selected));
+            if let Some(selected_pod) = selected_pod {
+                if let Ok(pod) = kubectl::find_pod_by_name(runner, &selected_pod.name, &app.tab().target_pod.namespace) {
+                    app.switch_to_pod(pod);
+                }
+            }
+        },
+        InternalAction::ToggleWrap => {
+            app.wrap = !app.wrap;
+            for pane in app.tab_mut().panes.iter_mut() {
+                pane.horizontal_scroll = 0;
+            }
+        },
+        InternalAction::ScrollDown => {
+            let pane = app.tab_mut().pane_mut();
+            pane.vertical_scroll = pane.vertical_scroll.saturating_add(1).min(pane.max_vertical_scroll);
+        }
+        InternalAction::PageDown => {
+            let pane = app.tab_mut().pane_mut();
+            pane.vertical_scroll = pane.vertical_scroll.saturating_add(20).min(pane.max_vertical_scroll);
+        }
+        InternalAction::ScrollUp => {
+            let pane = app.tab_mut().pane_mut();
+            pane.vertical_scroll = pane.vertical_scroll.saturating_sub(1);
+        }
+        InternalAction::PageUp => {
+            let pane = app.tab_mut().pane_mut();
+            pane.vertical_scroll = pane.vertical_scroll.saturating_sub(20);
+        }
+        InternalAction::ScrollRight => {
+            let pane = app.tab_mut().pane_mut();
+            pane.horizontal_scroll = pane.horizontal_scroll.saturating_add(4).min(pane.max_horizontal_scroll);
+        }
+        InternalAction::ScrollLeft => {
+            let pane = app.tab_mut().pane_mut();
+            pane.horizontal_scroll = pane.horizontal_scroll.saturating_sub(4);
+        }
+        InternalAction::ScrollToTop => app.tab_mut().pane_mut().vertical_scroll = 0,
+        InternalAction::ScrollToBottom => app.tab_mut().pane_mut().vertical_scroll = usize::MAX,
+        InternalAction::Help => {
+            app.show_help = true;
+            app.help_state = TableState::default();
+        }
+        InternalAction::CommandPalette => {
+            app.command_palette_pop_up = true;
+            app.palette_input.clear();
+            app.palette_state.select(Some(0));
+        }
+    }
+}
+
+/// Hands the terminal over to an interactive command, e.g. a shell or an editor, then takes it back.
+fn run_outside_tui<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mouse: bool,
+    command: impl FnOnce() -> Result<()>,
+) -> Result<()> where <B as Backend>::Error: Send, <B as Backend>::Error: Sync, <B as Backend>::Error: 'static {
+    terminal.clear().unwrap();
+    // Otherwise mouse movement reaches the command as escape codes.
+    if mouse {
+        execute!(io::stdout(), DisableMouseCapture).unwrap();
+    }
+    let result = command();
+    if mouse {
+        execute!(io::stdout(), EnableMouseCapture).unwrap();
+    }
+    terminal.clear().unwrap();
+    result
+}
+
+/// Handles a mouse event, based on what was drawn under the cursor on the last frame.
+fn handle_mouse<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    runner: &dyn KubectlRunner,
+    mouse: MouseEvent,
+) where <B as Backend>::Error: Send, <B as Backend>::Error: Sync, <B as Backend>::Error: 'static {
+    // Popups are keyboard driven.
+    if app.input_pop_up.is_some() || app.debug_offer.is_some() || app.debug_pop_up.is_some() || app.file_browser.is_some() || app.command_palette_pop_up || app.show_help {
+        return;
+    }
+
+    let position = Position::new(mouse.column, mouse.row);
+    let sidebar_rows = app.hit_map.sidebar_rows.filter(|rows| rows.contains(position));
 
-    let details_content = vec![render_action_text("📜 [f]etch logs ", InternalAction::FetchLogs, last_action),
-                                              render_action_text("📖 [l]ast logs ", InternalAction::LastLogs, last_action),
-                                              Span::from("📝 [v]im logs")];
+    match mouse.kind {
+        MouseEventKind::ScrollDown if sidebar_rows.is_some() => app.select_sidebar_pod(1),
+        MouseEventKind::ScrollUp if sidebar_rows.is_some() => app.select_sidebar_pod(-1),
+        MouseEventKind::ScrollDown => app.scroll_pane_at(position, 3, 0),
+        MouseEventKind::ScrollUp => app.scroll_pane_at(position, -3, 0),
+        MouseEventKind::ScrollRight => app.scroll_pane_at(position, 0, 4),
+        MouseEventKind::ScrollLeft => app.scroll_pane_at(position, 0, -4),
+        MouseEventKind::Down(MouseButton::Left) => {
+            app.selection = None;
+            if let Some(action) = app.hit_map.action_at(position) {
+                perform_action(terminal, app, runner, action);
+            } else if let Some(rows) = sidebar_rows {
+                let index = app.sidebar_state.offset() + (position.y - rows.y) as usize;
+                if app.sidebar_state.selected() == Some(index) {
+                    // Clicking the selected pod again switches to it.
+                    perform_action(terminal, app, runner, InternalAction::SwitchToSelectedPod);
+                } else if index < app.sidebar_pods.len() {
+                    app.sidebar_state.select(Some(index));
+                }
+            } else if let Some(index) = app.hit_map.pane_at(position) {
+                app.tab_mut().focused_pane = index;
+                app.selection = Some(Selection { pane: index, anchor: position, cursor: position });
+            }
+        }
+        MouseEventKind::Drag(MouseButton::Left) => {
+            if let Some(selection) = app.selection.as_mut() {
+                selection.cursor = position;
+            }
+        }
+        MouseEventKind::Up(MouseButton::Left) => {
+            match app.selection.as_mut() {
+                Some(selection) if selection.anchor != position => {
+                    selection.cursor = position;
+                    // Copied once the final selection has been drawn.
+                    app.copy_selection = true;
+                }
+                _ => app.selection = None,
+            }
+        }
+        _ => {}
+    }
+}
+
+fn ui(f: &mut Frame, app: &mut App) {
+    let size = f.area();
+    app.hit_map = HitMap::default();
 
     let chunks = Layout::vertical([
         Constraint::Min(1),
@@ -296,90 +1196,82 @@ fn ui(f: &mut Frame, app: &mut App, text: &str) {
     ])
         .split(size);
 
-    app.vertical_scroll_state = app.vertical_scroll_state.content_length(text.len());
-    app.horizontal_scroll_state = app.horizontal_scroll_state.content_length(text.len());
+    render_tab_bar(f, app, chunks[0]);
 
-    let paragraph = Paragraph::new(text)
-        .gray()
-        .block(
-            Block::bordered().white()
-            .title_top(Line::from(format!("{0} {pod_ns}/{pod_deployment}/{pod_name} ({1})", app.emoji, app.pod_status)).left_aligned().bold().white())
-            .title_top(Line::from(vec![
-                render_action_text("🔎 [d]esc ", InternalAction::ViewDesc, last_action),
-                Span::from("💻 [e]xec "),
-                Span::from("✏️ [E]dit "),
-                Span::from("🐞 de[b]ug "),
-                render_action_text("💀 [p]urge ", InternalAction::Purge, last_action),
-                Span::from("[q]uit ✖️")]).right_aligned().white())
-            .title_bottom(details_content).to_owned()
-            .title_bottom(Line::from(vec![
-                render_action_text("🗺️ [W/w]orld ", InternalAction::World, last_action),
-                render_action_text("[s]witch ⚙️", InternalAction::Switch, last_action)]).white().right_aligned()))
-        .style(Style::default().fg(Color::Rgb(186, 186, 186)))
-        .scroll((app.vertical_scroll as u16, app.horizontal_scroll as u16))
-        .wrap(Wrap { trim: true });
-
-    f.render_widget(paragraph, chunks[1]);
-
-    if app.last_action == Some(InternalAction::World) && app.show_pie_chart_for_running_pods == true {
-        let vertical_chunks = Layout::vertical([
-            Constraint::Percentage(20),
-            Constraint::Percentage(60),
-            Constraint::Percentage(20),
-        ])
-            .split(size);
-
-        let horiz_chunks = Layout::horizontal([
-            Constraint::Percentage(60),
-            Constraint::Percentage(40),
-        ])
-            .split(vertical_chunks[1]);
-        
-        // Do really bad way of counting by coutning substr matches
-        let total_pods: f64 = text.lines().count() as f64;
-        let running_pods: f64 = text.matches("🏃").count() as f64;
-        let starting_pods: f64 = text.matches("✨️").count() as f64;
-        // Create slices
-        let slices = vec![
-            PieSlice::new("Failed", (((&total_pods - (&starting_pods + &running_pods)) / &total_pods) * 100.0) + 0.0001, Color::Red),
-            PieSlice::new("Starting", ((&starting_pods / &total_pods) * 100.0) + 0.001, Color::Blue),
-            PieSlice::new("Running", ((&running_pods / &total_pods) * 100.0) + 0.001, Color::Green),
-        ];
-
-        let piechart = PieChart::new(slices)
-        .show_legend(true)
-        .show_percentages(true);
-
-        f.render_widget(Block::new(),  vertical_chunks[0]);
-        f.render_widget(piechart, horiz_chunks[1]);
+    let main_chunks = Layout::horizontal([
+        Constraint::Percentage(if app.show_sidebar { 40 } else { 0 }),
+        Constraint::Min(0),
+    ])
+        .split(chunks[1]);
+
+    let pane_areas = match app.layout {
+        PaneLayout::Single => Layout::vertical([Constraint::Percentage(100)]).split(main_chunks[1]),
+        PaneLayout::SideBySide => Layout::horizontal([Constraint::Percentage(50); 2]).split(main_chunks[1]),
+        PaneLayout::Stacked => Layout::vertical([Constraint::Percentage(50); 2]).split(main_chunks[1]),
+    };
+
+    for (index, pane_area) in pane_areas.iter().enumerate() {
+        render_pane(f, app, index, *pane_area);
     }
 
-    f.render_stateful_widget(
-        Scrollbar::new(ScrollbarOrientation::VerticalRight)
-            .begin_symbol(Some("↑"))
-            .end_symbol(Some("↓")),
-        chunks[1],
-        &mut app.vertical_scroll_state,
-    );
+    if app.show_sidebar {
+        render_sidebar(f, app, main_chunks[0]);
+    }
+
+    render_selection(f, app);
 
     if app.show_pod_deleted_pop_up {
-        let block = Block::bordered().title("💬 Alert").on_blue();
-        let message =  Paragraph::new("Pod deleted! Press 'q' to quit. :(".white()).wrap(Wrap { trim: true });
+        let block = Block::bordered().title(app.theme.label("💬 Alert")).bg(app.theme.alert);
+        let message =  Paragraph::new("Pod deleted! Press 'q' to quit. :(".fg(app.theme.foreground)).wrap(Wrap { trim: true });
         let area = centered_rect(60, 20, f.area());
         f.render_widget(Clear, area); //this clears out the background
         f.render_widget(message.clone().block(block), area);
     }
 
-    if app.new_pod_search_pop_up {
-        let mut block = Block::bordered().title("🔎 Enter new pod matcher (ESC to close)").on_black();
-        if app.show_switch_error_text {
-            block = Block::bordered().title("❌ Pod not found! Please search again.").on_red();
+    if let Some(reason) = &app.debug_offer {
+        let block = Block::bordered().title(app.theme.label("💬 No shell")).bg(app.theme.alert);
+        let message = format!("{reason}. Press 'y' to debug the pod with an ephemeral container instead, or any other key to cancel.");
+        let message = Paragraph::new(message.fg(app.theme.foreground)).wrap(Wrap { trim: true });
+        let area = centered_rect(60, 20, f.area());
+        f.render_widget(Clear, area); //this clears out the background
+        f.render_widget(message.block(block), area);
+    }
+
+    if let Some(browser) = &mut app.file_browser {
+        let progress = app.copy_job.as_ref().map(|job| job.progress());
+        files::render(f, browser, progress, centered_rect(60, 60, f.area()), &app.theme);
+    }
+
+    if let Some(form) = &app.debug_pop_up {
+        debug::render(f, form, centered_rect(60, 30, f.area()), &app.theme);
+    }
+
+    if app.show_help {
+        render_help(f, app);
+    }
+
+    if app.command_palette_pop_up {
+        render_command_palette(f, app);
+    }
+
+    if let Some(purpose) = app.input_pop_up {
+        let config_map_title = format!("✏️ Enter the ConfigMap to edit: {} (ESC to close)", app.config_maps.join(", "));
+        let (title, error_title) = match purpose {
+            InputPurpose::SwitchPod => ("🔎 Enter new pod matcher (ESC to close)", "❌ Pod not found! Please search again."),
+            InputPurpose::NewTab => ("🗂️ Enter pod matcher for new tab (ESC to close)", "❌ Pod not found! Please search again."),
+            InputPurpose::CopyMatches => ("📋 Enter a regex for the lines to copy (ESC to close)", "❌ No lines match! Please try again."),
+            InputPurpose::ExecCommand => ("💻 Enter a command to run in the pod (ESC to close)", "❌ Please enter a command, closing any quotes."),
+            InputPurpose::EditConfigMap => (config_map_title.as_str(), "❌ The pod doesn't use that ConfigMap! Please try again."),
+        };
+        let mut block = Block::bordered().title(app.theme.label(title)).bg(app.theme.background);
+        if app.show_input_error {
+            block = Block::bordered().title(app.theme.label(error_title)).bg(app.theme.error);
         }
         let area = centered_rect(60, 20, f.area());
 
-        let input = Paragraph::new(app.input_text.as_str().white())
+        let input = Paragraph::new(app.input_text.as_str().fg(app.theme.foreground))
             .style(
-                Style::default().bg(Color::Black)
+                Style::default().bg(app.theme.background)
             );
 
         f.render_widget(Clear, area); //this clears out the background
@@ -387,6 +1279,338 @@ fn ui(f: &mut Frame, app: &mut App, text: &str) {
     }
 }
 
+/// Renders one of the current tab's panes. The first pane carries the pod details and action bars.
+fn render_pane(f: &mut Frame, app: &mut App, index: usize, area: Rect) {
+    // Leave a row below the pane for the horizontal scrollbar when lines aren't wrapped.
+    let pane_chunks = Layout::vertical([
+        Constraint::Min(0),
+        Constraint::Length(if app.wrap { 0 } else { 1 }),
+    ])
+        .split(area);
+    let pane_area = pane_chunks[0];
+    let inner_area = Block::bordered().inner(pane_area);
+    app.hit_map.panes.push(pane_area);
+
+    // The world view shares its pane with a chart of the pods in it.
+    let tab = &app.tabs[app.active_tab];
+    let is_charting_world = tab.panes[index].view == PaneView::World && !tab.world.is_empty();
+    let [text_area, chart_area] = Layout::horizontal([
+        Constraint::Min(0),
+        Constraint::Percentage(if is_charting_world { 40 } else { 0 }),
+    ])
+        .areas(inner_area);
+
+    let is_split = app.layout != PaneLayout::Single;
+    let theme = &app.theme;
+    let tab = &mut app.tabs[app.active_tab];
+    let is_focused = tab.focused_pane == index;
+    let pod_name = &tab.target_pod.name;
+    let pod_deployment = &tab.target_pod.deployment;
+    let pod_ns = &tab.target_pod.namespace;
+    let last_action = &tab.last_action;
+    let pane = &mut tab.panes[index];
+
+    // The usage view draws its own charts instead.
+    let text = if pane.view == PaneView::Metrics { "" } else { pane.text.as_str() };
+    let mut paragraph = Paragraph::new(text);
+    if app.wrap {
+        // Don't trim, otherwise leading indentation in stack traces is lost.
+        paragraph = paragraph.wrap(Wrap { trim: false });
+    }
+
+    // Scroll offsets are in rendered rows, so wrapped lines count for as many rows as they take up.
+    let content_height = paragraph.line_count(text_area.width);
+    let content_width = if app.wrap { 0 } else { paragraph.line_width() };
+    pane.viewport = text_area;
+    pane.max_vertical_scroll = content_height.saturating_sub(text_area.height as usize);
+    pane.max_horizontal_scroll = content_width.saturating_sub(text_area.width as usize);
+    pane.vertical_scroll = pane.vertical_scroll.min(pane.max_vertical_scroll);
+    pane.horizontal_scroll = pane.horizontal_scroll.min(pane.max_horizontal_scroll);
+
+    pane.vertical_scroll_state = pane.vertical_scroll_state
+        .content_length(pane.max_vertical_scroll + 1)
+        .viewport_content_length(text_area.height as usize)
+        .position(pane.vertical_scroll);
+    pane.horizontal_scroll_state = pane.horizontal_scroll_state
+        .content_length(pane.max_horizontal_scroll + 1)
+        .viewport_content_length(text_area.width as usize)
+        .position(pane.horizontal_scroll);
+
+    let border_colour = if is_split && !is_focused { theme.inactive_border } else { theme.foreground };
+
+    let block = if index == 0 {
+        let wrap_text = if app.wrap { "↩️ [z]wrap on " } else { "➡️ [z]wrap off " };
+
+        // The titles span the whole pane, chart included, so their click targets do too.
+        let top_row = Rect { y: pane_area.top(), height: 1, ..inner_area };
+        let bottom_row = Rect { y: pane_area.bottom().saturating_sub(1), height: 1, ..inner_area };
+        let actions = &mut app.hit_map.actions;
+
+        let details_content = clickable_title(vec![
+            (render_action_text("📜 [f]etch logs ", InternalAction::FetchLogs, last_action, theme), Some(InternalAction::FetchLogs)),
+            (render_action_text("📖 [l]ast logs ", InternalAction::LastLogs, last_action, theme), Some(InternalAction::LastLogs)),
+            (render_action_text("📅 eve[n]ts ", InternalAction::Events, last_action, theme), Some(InternalAction::Events)),
+            (render_action_text("📈 [u]sage ", InternalAction::Metrics, last_action, theme), Some(InternalAction::Metrics)),
+            (Span::from(theme.label("📝 [v]im logs ")).fg(theme.foreground), Some(InternalAction::Vim)),
+            (Span::from(theme.label(wrap_text)).fg(theme.foreground), Some(InternalAction::ToggleWrap))], bottom_row, false, actions);
+
+        let top_actions = clickable_title(vec![
+            (render_action_text("🔎 [d]esc ", InternalAction::ViewDesc, last_action, theme), Some(InternalAction::ViewDesc)),
+            (Span::from(theme.label("💻 [e]xec ")), Some(InternalAction::Exec)),
+            (Span::from(theme.label("✏️ [E]dit ")), Some(InternalAction::Edit)),
+            (Span::from(theme.label("🐞 de[b]ug ")), Some(InternalAction::Debug)),
+            (render_action_text("💀 [p]urge ", InternalAction::Purge, last_action, theme), Some(InternalAction::Purge)),
+            (Span::from(theme.label("[q]uit ✖️")), Some(InternalAction::Quit))], top_row, true, actions);
+
+        let bottom_actions = clickable_title(vec![
+            (render_action_text("🗺️ [W/w]orld ", InternalAction::World, last_action, theme), Some(InternalAction::World)),
+            (Span::from(theme.label("📦 [S]idebar ")), Some(InternalAction::ToggleSidebar)),
+            (Span::from(theme.label("🔀 [|/-]split ")), Some(InternalAction::SplitSideBySide)),
+            (Span::from(theme.label("[o]ther ")), Some(InternalAction::FocusOtherPane)),
+            (render_action_text("[s]witch ⚙️", InternalAction::Switch, last_action, theme), Some(InternalAction::Switch))], bottom_row, true, actions);
+
+        Block::bordered().fg(border_colour)
+            .title_top(Line::from(format!("{0} {pod_ns}/{pod_deployment}/{pod_name} ({1})", app.emoji, tab.pod_status)).left_aligned().bold().fg(theme.foreground))
+            .title_top(top_actions.fg(theme.foreground))
+            .title_bottom(details_content)
+            .title_bottom(bottom_actions.fg(theme.foreground))
+    } else {
+        Block::bordered().fg(border_colour)
+            .title_top(Line::from(theme.label(pane.view.title())).left_aligned().bold().fg(theme.foreground))
+    };
+
+    let paragraph = paragraph
+        .style(Style::default().fg(theme.text))
+        .scroll((pane.vertical_scroll as u16, pane.horizontal_scroll as u16));
+
+    f.render_widget(block, pane_area);
+    f.render_widget(paragraph, text_area);
+
+    f.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some(if theme.ascii { "^" } else { "↑" }))
+            .end_symbol(Some(if theme.ascii { "v" } else { "↓" })),
+        pane_area,
+        &mut pane.vertical_scroll_state,
+    );
+
+    if !app.wrap {
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::HorizontalBottom)
+                .begin_symbol(Some(if theme.ascii { "<" } else { "←" }))
+                .end_symbol(Some(if theme.ascii { ">" } else { "→" })),
+            pane_chunks[1],
+            &mut pane.horizontal_scroll_state,
+        );
+    }
+
+    if pane.view == PaneView::Metrics {
+        metrics::render(f, &tab.metrics, text_area, theme);
+    }
+
+    if is_charting_world {
+        world::render(f, &tab.world, app.world_breakdown, chart_area, theme);
+    }
+}
+
+/// Builds a border title from spans that may trigger an action when clicked, noting where `Block` will draw
+/// the clickable ones along `row`.
+fn clickable_title<'a>(
+    items: Vec<(Span<'a>, Option<InternalAction>)>,
+    row: Rect,
+    right_aligned: bool,
+    actions: &mut Vec<(Rect, InternalAction)>,
+) -> Line<'a> {
+    let width = items.iter().map(|(span, _)| span.width() as u16).sum::<u16>();
+    let mut x = if right_aligned { row.right().saturating_sub(width).max(row.left()) } else { row.left() };
+
+    for (span, action) in &items {
+        let span_width = span.width() as u16;
+        let area = Rect { x, width: span_width, ..row }.intersection(row);
+        if let Some(action) = action {
+            if !area.is_empty() {
+                actions.push((area, *action));
+            }
+        }
+        x = x.saturating_add(span_width);
+    }
+
+    let line = Line::from(items.into_iter().map(|(span, _)| span).collect::<Vec<_>>());
+    if right_aligned { line.right_aligned() } else { line.left_aligned() }
+}
+
+/// Highlights the text being selected with the mouse, keeping a copy of it for the clipboard.
+fn render_selection(f: &mut Frame, app: &mut App) {
+    let Some(selection) = app.selection else { return };
+    let Some(pane_area) = app.hit_map.panes.get(selection.pane) else { return };
+    let text_area = Block::bordered().inner(*pane_area);
+    if text_area.is_empty() {
+        return;
+    }
+
+    let clamp = |position: Position| Position::new(
+        position.x.clamp(text_area.left(), text_area.right() - 1),
+        position.y.clamp(text_area.top(), text_area.bottom() - 1),
+    );
+    let (start, end) = selection.bounds();
+    let (start, end) = (clamp(start), clamp(end));
+
+    let buffer = f.buffer_mut();
+    let mut lines = Vec::new();
+    for y in start.y..=end.y {
+        let first = if y == start.y { start.x } else { text_area.left() };
+        let last = if y == end.y { end.x } else { text_area.right() - 1 };
+        let mut line = String::new();
+        let mut hidden_cells = 0;
+
+        for x in first..=last {
+            let cell = &mut buffer[(x, y)];
+            cell.modifier.insert(Modifier::REVERSED);
+            // Wide characters cover the cells after them, which only hold padding.
+            if hidden_cells > 0 {
+                hidden_cells -= 1;
+                continue;
+            }
+            hidden_cells = Span::raw(cell.symbol()).width().saturating_sub(1);
+            line.push_str(cell.symbol());
+        }
+
+        lines.push(line.trim_end().to_string());
+    }
+
+    app.selected_text = lines.join("\n");
+}
+
+fn render_help(f: &mut Frame, app: &mut App) {
+    let theme = &app.theme;
+    let rows = ACTIONS.iter().map(|spec| Row::new(vec![
+        Cell::from(theme.label(spec.key_label)).bold().fg(theme.foreground),
+        Cell::from(spec.name).fg(theme.foreground),
+        Cell::from(spec.description).fg(theme.muted),
+    ]));
+
+    let table = Table::new(rows, [
+        Constraint::Length(12),
+        Constraint::Length(24),
+        Constraint::Fill(1),
+    ])
+        .header(Row::new(vec!["KEY", "ACTION", "DESCRIPTION"]).bold().fg(theme.highlight))
+        .block(
+            Block::bordered().fg(theme.foreground)
+            .title_top(Line::from(theme.label("❓ Help")).left_aligned().bold())
+            .title_bottom(Line::from(theme.label("↑↓ scroll, any other key to close, [:] for the command palette")).right_aligned()))
+        .style(Style::default().bg(theme.background));
+
+    let area = centered_rect(80, 80, f.area());
+    f.render_widget(Clear, area); //this clears out the background
+    f.render_stateful_widget(table, area, &mut app.help_state);
+}
+
+fn render_command_palette(f: &mut Frame, app: &mut App) {
+    let area = centered_rect(60, 50, f.area());
+    let palette_chunks = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(0),
+    ])
+        .split(area);
+
+    let theme = &app.theme;
+    let input = Paragraph::new(format!(":{}", app.palette_input).fg(theme.foreground))
+        .block(Block::bordered().fg(theme.foreground).title(theme.label("🎨 Command palette (ESC to close)")))
+        .style(Style::default().bg(theme.background));
+
+    let rows = actions::search(&app.palette_input).into_iter().map(|spec| Row::new(vec![
+        Cell::from(spec.name).fg(theme.foreground),
+        Cell::from(theme.label(spec.key_label)).bold().fg(theme.foreground),
+        Cell::from(spec.description).fg(theme.muted),
+    ]));
+
+    let results = Table::new(rows, [
+        Constraint::Length(24),
+        Constraint::Length(12),
+        Constraint::Fill(1),
+    ])
+        .row_highlight_style(Style::default().bg(theme.selection))
+        .block(Block::bordered().fg(theme.foreground).title_bottom(Line::from(theme.label("↑↓ select ⏎ run")).right_aligned()))
+        .style(Style::default().bg(theme.background));
+
+    f.render_widget(Clear, area); //this clears out the background
+    f.render_widget(input, palette_chunks[0]);
+    f.render_stateful_widget(results, palette_chunks[1], &mut app.palette_state);
+}
+
+fn render_tab_bar(f: &mut Frame, app: &App, area: Rect) {
+    // Recent status messages, e.g. where copied text went, take the place of the hint.
+    let status_message = app.status_message.as_ref()
+        .filter(|(_, shown_at)| shown_at.elapsed() < STATUS_MESSAGE_DURATION);
+    let mut hint = match status_message {
+        Some((message, _)) => Line::from(format!("{message} ")).right_aligned().fg(app.theme.warning),
+        None => Line::from(app.theme.label("[?]help [:]commands [t]new tab [x]close ⇥ next tab ")).right_aligned().fg(app.theme.muted),
+    };
+    if let Some(newer_release) = &app.newer_release {
+        hint.spans.insert(0, Span::from(app.theme.label(&format!("↑ {newer_release} available ")).into_owned()).fg(app.theme.info));
+    }
+    let tab_bar_chunks = Layout::horizontal([
+        Constraint::Min(0),
+        Constraint::Length(hint.width() as u16),
+    ])
+        .split(area);
+
+    let titles = app.tabs.iter().enumerate()
+        .map(|(index, tab)| format!("{} {}", index + 1, tab.target_pod.name));
+
+    let tabs = Tabs::new(titles)
+        .select(app.active_tab)
+        .fg(app.theme.muted)
+        .highlight_style(Style::default().fg(app.theme.highlight).bold());
+
+    f.render_widget(tabs, tab_bar_chunks[0]);
+    f.render_widget(hint, tab_bar_chunks[1]);
+}
+
+fn render_sidebar(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = &app.theme;
+    let target_pod = &app.tabs[app.active_tab].target_pod;
+    let rows = app.sidebar_pods.iter().map(|pod| {
+        let is_target = pod.name == target_pod.name;
+        let status_colour = match pod.status.as_str() {
+            "Running" => theme.success,
+            "Completed" | "Succeeded" => theme.info,
+            "Pending" | "ContainerCreating" | "PodInitializing" | "Terminating" => theme.warning,
+            _ => theme.error,
+        };
+
+        let row = Row::new(vec![
+            Cell::from(format!("{}{}", if is_target { theme.label("▶ ") } else { "  ".into() }, pod.name)),
+            Cell::from(pod.ready.as_str()),
+            Cell::from(pod.status.as_str()).fg(status_colour),
+            Cell::from(pod.restarts.as_str()),
+            Cell::from(pod.age.as_str()),
+        ]);
+
+        if is_target { row.bold() } else { row }
+    });
+
+    let table = Table::new(rows, [
+        Constraint::Fill(3),
+        Constraint::Length(5),
+        Constraint::Fill(2),
+        Constraint::Fill(1),
+        Constraint::Length(4),
+    ])
+        .header(Row::new(vec!["NAME", "READY", "STATUS", "RESTARTS", "AGE"]).fg(theme.muted))
+        .row_highlight_style(Style::default().bg(theme.selection))
+        .block(
+            Block::bordered().fg(theme.foreground)
+            .title_top(Line::from(theme.label(&format!("📦 {}", target_pod.namespace)).into_owned()).left_aligned().bold())
+            .title_bottom(Line::from(theme.label("↑↓ select ⏎ switch [S]idebar")).right_aligned()));
+
+    let rows = Block::bordered().inner(area);
+    app.hit_map.sidebar_rows = Some(Rect { y: rows.y + 1, height: rows.height.saturating_sub(1), ..rows });
+
+    f.render_stateful_widget(table, area, &mut app.sidebar_state);
+}
+
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::vertical([
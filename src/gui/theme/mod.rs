@@ -0,0 +1,221 @@
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use color_eyre::eyre::{eyre, Result};
+use ratatui::style::Color;
+
+use crate::settings::Settings;
+
+/// Plain text stand-ins for symbols in the UI that mean something. Longer sequences come first so that
+/// e.g. an emoji's variation selector is replaced along with it.
+const ASCII_LABELS: &[(&str, &str)] = &[
+    ("⇧⇥", "S-Tab"),
+    ("⇥", "Tab"),
+    ("⏎", "Enter"),
+    ("⌫", "Bksp"),
+    ("↑↓", "Up/Down"),
+    ("↑", "^"),
+    ("↓", "v"),
+    ("←", "<"),
+    ("→", ">"),
+    ("▶", ">"),
+    ("❌ ", "[!] "),
+    ("✖️", "x"),
+    (" ⚙️", ""),
+];
+
+/// The colours the TUI is drawn with, and whether emoji are swapped for text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    /// Titles, actions, borders and popup text.
+    pub foreground: Color,
+    /// The text inside panes.
+    pub text: Color,
+    /// Hints, headers and descriptions.
+    pub muted: Color,
+    /// The border of a pane without focus.
+    pub inactive_border: Color,
+    /// The last action taken, the current tab and other things to draw the eye to.
+    pub highlight: Color,
+    /// Behind popups.
+    pub background: Color,
+    /// Behind selected rows.
+    pub selection: Color,
+    /// Behind alert popups.
+    pub alert: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    /// Worse than an error, e.g. a container stuck crash looping.
+    pub critical: Color,
+    pub info: Color,
+    /// Whether to swap emoji for plain text labels.
+    pub ascii: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Theme {
+            foreground: Color::White,
+            text: Color::Rgb(186, 186, 186),
+            muted: Color::Gray,
+            inactive_border: Color::DarkGray,
+            highlight: Color::Blue,
+            background: Color::Black,
+            selection: Color::DarkGray,
+            alert: Color::Blue,
+            success: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+            critical: Color::Magenta,
+            info: Color::Blue,
+            ascii: false,
+        }
+    }
+
+    /// For terminals with a light background.
+    pub fn light() -> Self {
+        Theme {
+            foreground: Color::Black,
+            text: Color::Rgb(58, 58, 58),
+            muted: Color::DarkGray,
+            inactive_border: Color::Gray,
+            highlight: Color::Blue,
+            background: Color::White,
+            selection: Color::Rgb(208, 208, 208),
+            alert: Color::LightBlue,
+            success: Color::Green,
+            warning: Color::Rgb(175, 95, 0),
+            error: Color::Red,
+            critical: Color::Magenta,
+            info: Color::Blue,
+            ascii: false,
+        }
+    }
+
+    /// Bright colours on black, with nothing dimmed.
+    pub fn high_contrast() -> Self {
+        Theme {
+            foreground: Color::White,
+            text: Color::White,
+            muted: Color::White,
+            inactive_border: Color::Gray,
+            highlight: Color::LightYellow,
+            background: Color::Black,
+            selection: Color::Blue,
+            alert: Color::Blue,
+            success: Color::LightGreen,
+            warning: Color::LightYellow,
+            error: Color::LightRed,
+            critical: Color::LightMagenta,
+            info: Color::LightCyan,
+            ascii: false,
+        }
+    }
+
+    /// Looks up a built-in theme by name.
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            "high-contrast" => Some(Theme::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// Builds the theme picked in the user's settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - The user's settings, naming a built-in theme or one of their custom themes.
+    ///
+    /// # Returns
+    ///
+    /// * `Theme` - The theme, with any custom colours applied on top of its base.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the theme, a custom theme's base, or one of its colours isn't recognised.
+    pub fn from_settings(settings: &Settings) -> Result<Self> {
+        let mut theme = match (Theme::builtin(&settings.theme), settings.themes.get(&settings.theme)) {
+            (_, Some(custom)) => {
+                let base = custom.base.as_deref().unwrap_or("dark");
+                let mut theme = Theme::builtin(base).ok_or_else(|| eyre!("Unknown base theme '{base}' for theme '{}'", settings.theme))?;
+                for (name, value) in &custom.colors {
+                    theme.set_color(name, value)?;
+                }
+                theme
+            }
+            (Some(theme), None) => theme,
+            (None, None) => return Err(eyre!("Unknown theme '{}', expected dark, light, high-contrast or one defined under [themes]", settings.theme)),
+        };
+
+        theme.ascii = settings.ascii;
+        Ok(theme)
+    }
+
+    /// Replaces one of the theme's colours, given its name and a colour name (e.g. `light-red`), index or `#rrggbb`.
+    fn set_color(&mut self, name: &str, value: &str) -> Result<()> {
+        let color = Color::from_str(value).map_err(|_| eyre!("Invalid colour '{value}' for '{name}'"))?;
+
+        let slot = match name {
+            "foreground" => &mut self.foreground,
+            "text" => &mut self.text,
+            "muted" => &mut self.muted,
+            "inactive_border" => &mut self.inactive_border,
+            "highlight" => &mut self.highlight,
+            "background" => &mut self.background,
+            "selection" => &mut self.selection,
+            "alert" => &mut self.alert,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            "critical" => &mut self.critical,
+            "info" => &mut self.info,
+            _ => return Err(eyre!("Unknown theme colour '{name}'")),
+        };
+
+        *slot = color;
+        Ok(())
+    }
+
+    /// Prepares UI text for display, swapping emoji for text labels in ASCII mode.
+    pub fn label<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.ascii {
+            Cow::Owned(ascii_label(text))
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+}
+
+/// Swaps the emoji and symbols in UI text for plain ASCII. Known symbols get a label, anything else that
+/// isn't ASCII is dropped along with the space after it.
+pub fn ascii_label(text: &str) -> String {
+    let mut text = text.to_string();
+    for (symbol, label) in ASCII_LABELS {
+        text = text.replace(symbol, label);
+    }
+
+    let mut label = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii() {
+            label.push(c);
+        } else {
+            while chars.next_if(|next| !next.is_ascii()).is_some() {}
+            chars.next_if_eq(&' ');
+        }
+    }
+
+    label
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,75 @@
+use super::*;
+
+use std::collections::HashMap;
+
+use crate::settings::CustomTheme;
+
+#[test]
+fn test_ascii_label_swaps_emoji() {
+    assert_eq!("[f]etch logs ", ascii_label("📜 [f]etch logs "));
+    assert_eq!("[q]uit x", ascii_label("[q]uit ✖️"));
+    assert_eq!("[s]witch", ascii_label("[s]witch ⚙️"));
+    assert_eq!("[W/w]orld ", ascii_label("🗺️ [W/w]orld "));
+    assert_eq!("[!] Pod not found!", ascii_label("❌ Pod not found!"));
+    assert_eq!("Up/Down select Enter run", ascii_label("↑↓ select ⏎ run"));
+    assert_eq!("j/v", ascii_label("j/↓"));
+}
+
+#[test]
+fn test_ascii_label_keeps_ascii_text() {
+    assert_eq!("ns/deployment/my-pod (Running)", ascii_label("ns/deployment/my-pod (Running)"));
+}
+
+#[test]
+fn test_label_only_swaps_in_ascii_mode() {
+    let theme = Theme::dark();
+    let ascii_theme = Theme { ascii: true, ..Theme::dark() };
+
+    assert_eq!("📦 ns", theme.label("📦 ns"));
+    assert_eq!("ns", ascii_theme.label("📦 ns"));
+}
+
+#[test]
+fn test_from_settings_builtin_success() {
+    let settings = Settings { theme: "high-contrast".to_string(), ascii: true, ..Default::default() };
+
+    let theme = Theme::from_settings(&settings).unwrap();
+
+    assert_eq!(Theme { ascii: true, ..Theme::high_contrast() }, theme);
+}
+
+#[test]
+fn test_from_settings_custom_success() {
+    let custom = CustomTheme {
+        base: Some("light".to_string()),
+        colors: HashMap::from([("highlight".to_string(), "#ff8700".to_string()), ("error".to_string(), "magenta".to_string())]),
+    };
+    let settings = Settings { theme: "mine".to_string(), themes: HashMap::from([("mine".to_string(), custom)]), ..Default::default() };
+
+    let theme = Theme::from_settings(&settings).unwrap();
+
+    assert_eq!(Theme { highlight: Color::Rgb(255, 135, 0), error: Color::Magenta, ..Theme::light() }, theme);
+}
+
+#[test]
+fn test_from_settings_unknown_theme_failure() {
+    let settings = Settings { theme: "solarized".to_string(), ..Default::default() };
+
+    assert!(Theme::from_settings(&settings).is_err());
+}
+
+#[test]
+fn test_from_settings_invalid_colour_failure() {
+    let custom = CustomTheme { base: None, colors: HashMap::from([("highlight".to_string(), "not-a-colour".to_string())]) };
+    let settings = Settings { theme: "mine".to_string(), themes: HashMap::from([("mine".to_string(), custom)]), ..Default::default() };
+
+    assert!(Theme::from_settings(&settings).is_err());
+}
+
+#[test]
+fn test_from_settings_unknown_colour_failure() {
+    let custom = CustomTheme { base: None, colors: HashMap::from([("sparkles".to_string(), "red".to_string())]) };
+    let settings = Settings { theme: "mine".to_string(), themes: HashMap::from([("mine".to_string(), custom)]), ..Default::default() };
+
+    assert!(Theme::from_settings(&settings).is_err());
+}
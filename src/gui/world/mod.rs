@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Padding, Row, Table};
+use ratatui::Frame;
+use tui_piechart::{PieChart, PieSlice};
+
+use crate::gui::theme::Theme;
+use crate::kubectl::{PodHealth, PodPhase};
+
+/// How the world view's pod chart is split up, beyond the overall count per phase.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Breakdown {
+    #[default]
+    Phase,
+    Namespace,
+    Owner,
+}
+
+impl Breakdown {
+    /// The breakdown after this one, wrapping around.
+    pub fn next(&self) -> Self {
+        match self {
+            Breakdown::Phase => Breakdown::Namespace,
+            Breakdown::Namespace => Breakdown::Owner,
+            Breakdown::Owner => Breakdown::Phase,
+        }
+    }
+
+    /// Whether pods from every namespace are needed, rather than just the pod's own.
+    pub fn all_namespaces(&self) -> bool {
+        *self == Breakdown::Namespace
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Breakdown::Phase => "pods by phase",
+            Breakdown::Namespace => "pods by namespace",
+            Breakdown::Owner => "pods by owner",
+        }
+    }
+}
+
+/// The number of pods in each `PodHealth`, in the order of `PodHealth::ALL`.
+pub type HealthCounts = [usize; PodHealth::ALL.len()];
+
+/// Counts pods by health.
+pub fn count(pods: &[&PodPhase]) -> HealthCounts {
+    let mut counts = HealthCounts::default();
+    for pod in pods {
+        counts[health_index(pod.health)] += 1;
+    }
+
+    counts
+}
+
+/// Counts pods by health within each namespace or owner, ordered by name. Empty for `Breakdown::Phase`.
+pub fn group(pods: &[PodPhase], breakdown: Breakdown) -> Vec<(String, HealthCounts)> {
+    let mut groups: BTreeMap<&str, Vec<&PodPhase>> = BTreeMap::new();
+    for pod in pods {
+        let key = match breakdown {
+            Breakdown::Phase => return Vec::new(),
+            Breakdown::Namespace => pod.namespace.as_str(),
+            Breakdown::Owner => pod.owner.as_str(),
+        };
+        groups.entry(key).or_default().push(pod);
+    }
+
+    groups.into_iter()
+        .map(|(name, pods)| (name.to_string(), count(&pods)))
+        .collect()
+}
+
+fn health_index(health: PodHealth) -> usize {
+    PodHealth::ALL.iter().position(|candidate| *candidate == health).unwrap_or_default()
+}
+
+fn health_colour(health: PodHealth, theme: &Theme) -> Color {
+    match health {
+        PodHealth::Running => theme.success,
+        PodHealth::Pending => theme.warning,
+        PodHealth::Succeeded => theme.info,
+        PodHealth::Failed => theme.error,
+        PodHealth::CrashLoopBackOff => theme.critical,
+        PodHealth::Unknown => theme.muted,
+    }
+}
+
+/// A column heading short enough to fit several side by side.
+fn short_label(health: PodHealth) -> &'static str {
+    match health {
+        PodHealth::Running => "Run",
+        PodHealth::Pending => "Pend",
+        PodHealth::Succeeded => "Done",
+        PodHealth::Failed => "Fail",
+        PodHealth::CrashLoopBackOff => "Crash",
+        PodHealth::Unknown => "?",
+    }
+}
+
+/// Draws a pie chart of pods by health, and below it a table of the breakdown by namespace or owner.
+///
+/// # Arguments
+///
+/// * `pods` - The pods to chart. Nothing is drawn if there are none.
+/// * `breakdown` - How to split up the pods in the table.
+/// * `area` - Where to draw the chart.
+/// * `theme` - The colours to draw with.
+pub fn render(f: &mut Frame, pods: &[PodPhase], breakdown: Breakdown, area: Rect, theme: &Theme) {
+    if pods.is_empty() {
+        return;
+    }
+
+    let block = Block::new()
+        .borders(Borders::LEFT)
+        .padding(Padding::left(1))
+        .fg(theme.inactive_border)
+        .title_top(Line::from(format!(" {} ", breakdown.title())).fg(theme.foreground))
+        .title_bottom(Line::from(" [B]reakdown ").right_aligned().fg(theme.muted));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let counts = count(&pods.iter().collect::<Vec<_>>());
+    let groups = group(pods, breakdown);
+    let [pie_area, table_area] = Layout::vertical([
+        Constraint::Min(0),
+        Constraint::Length(if groups.is_empty() { 0 } else { (groups.len() as u16 + 1).min(inner.height / 2) }),
+    ])
+        .areas(inner);
+
+    // Slices with no pods are left out, rather than padded so that every phase shows up.
+    let present: Vec<PodHealth> = PodHealth::ALL.into_iter().filter(|health| counts[health_index(*health)] > 0).collect();
+    let labels: Vec<String> = present.iter()
+        .map(|health| format!("{} {}", health.label(), counts[health_index(*health)]))
+        .collect();
+    let slices = present.iter().zip(&labels)
+        .map(|(health, label)| PieSlice::new(label, counts[health_index(*health)] as f64, health_colour(*health, theme)))
+        .collect();
+
+    let mut pie_chart = PieChart::new(slices)
+        .style(Style::default().fg(theme.text))
+        .show_legend(true)
+        .show_percentages(false);
+    if theme.ascii {
+        pie_chart = pie_chart.pie_char('#').legend_marker("#");
+    }
+    f.render_widget(pie_chart, pie_area);
+
+    if groups.is_empty() {
+        return;
+    }
+
+    let header = Row::new(
+        std::iter::once(Cell::from(""))
+            .chain(present.iter().map(|health| Cell::from(short_label(*health)).fg(health_colour(*health, theme))))
+    ).fg(theme.muted);
+    let rows = groups.iter().map(|(name, group_counts)| {
+        Row::new(
+            std::iter::once(Cell::from(name.as_str()))
+                .chain(present.iter().map(|health| match group_counts[health_index(*health)] {
+                    0 => Cell::from("-"),
+                    count => Cell::from(count.to_string()),
+                }))
+        )
+    });
+    let widths = std::iter::once(Constraint::Min(8))
+        .chain(present.iter().map(|health| Constraint::Length(short_label(*health).len().max(3) as u16)));
+
+    f.render_widget(Table::new(rows, widths).header(header).style(Style::default().fg(theme.text)), table_area);
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,64 @@
+use super::*;
+
+fn pod(namespace: &str, owner: &str, health: PodHealth) -> PodPhase {
+    PodPhase { name: format!("{owner}-pod"), namespace: namespace.to_string(), owner: owner.to_string(), health }
+}
+
+#[test]
+fn test_count_by_health() {
+    let pods = [
+        pod("default", "deployment/api", PodHealth::Running),
+        pod("default", "deployment/api", PodHealth::Running),
+        pod("default", "job/migrate", PodHealth::Succeeded),
+        pod("default", "deployment/web", PodHealth::CrashLoopBackOff),
+    ];
+
+    let counts = count(&pods.iter().collect::<Vec<_>>());
+
+    assert_eq!([2, 0, 1, 0, 1, 0], counts);
+}
+
+#[test]
+fn test_group_by_owner() {
+    let pods = [
+        pod("default", "deployment/web", PodHealth::Pending),
+        pod("default", "deployment/api", PodHealth::Running),
+        pod("other", "deployment/api", PodHealth::Failed),
+    ];
+
+    let groups = group(&pods, Breakdown::Owner);
+
+    assert_eq!(vec![
+        ("deployment/api".to_string(), [1, 0, 0, 1, 0, 0]),
+        ("deployment/web".to_string(), [0, 1, 0, 0, 0, 0]),
+    ], groups);
+}
+
+#[test]
+fn test_group_by_namespace() {
+    let pods = [
+        pod("other", "deployment/api", PodHealth::Unknown),
+        pod("default", "deployment/api", PodHealth::Running),
+    ];
+
+    let groups = group(&pods, Breakdown::Namespace);
+
+    assert_eq!(vec![
+        ("default".to_string(), [1, 0, 0, 0, 0, 0]),
+        ("other".to_string(), [0, 0, 0, 0, 0, 1]),
+    ], groups);
+}
+
+#[test]
+fn test_group_by_phase_is_empty() {
+    let pods = [pod("default", "deployment/api", PodHealth::Running)];
+
+    assert!(group(&pods, Breakdown::Phase).is_empty());
+}
+
+#[test]
+fn test_breakdown_cycles() {
+    assert_eq!(Breakdown::Namespace, Breakdown::Phase.next());
+    assert_eq!(Breakdown::Owner, Breakdown::Namespace.next());
+    assert_eq!(Breakdown::Phase, Breakdown::Owner.next());
+}
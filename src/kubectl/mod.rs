@@ -1,4 +1,6 @@
-use std::{io::Write, process::{Command, Stdio}};
+use std::{collections::{BTreeMap, HashMap}, io::{Read, Write}, process::{Child, Command, Stdio}};
+
+use base64::Engine;
 
 use color_eyre::eyre::{Context, Result};
 use regex::Regex;
@@ -7,6 +9,36 @@ use thiserror::Error;
 pub trait KubectlRunner {
     fn run_commands(&self, args: &[&str]) -> Result<String>;
     fn spawn_shell(&self, args: &[&str]) -> Result<()>;
+    /// Starts a long running command, like `kubectl cp`, without waiting for it to finish.
+    fn spawn_background(&self, args: &[&str]) -> Result<Box<dyn BackgroundCommand>>;
+}
+
+/// A `kubectl` command running in the background.
+pub trait BackgroundCommand {
+    /// Checks on the command without waiting for it.
+    ///
+    /// # Returns
+    /// * `None` - While the command is still running.
+    /// * `Some(Ok(()))` - Once the command has succeeded.
+    /// * `Some(Err(_))` - If the command failed, with what it wrote to stderr.
+    fn try_finish(&mut self) -> Option<Result<()>>;
+}
+
+impl BackgroundCommand for Child {
+    fn try_finish(&mut self) -> Option<Result<()>> {
+        match self.try_wait() {
+            Ok(Some(status)) if status.success() => Some(Ok(())),
+            Ok(Some(_)) => {
+                let mut stderr = String::new();
+                if let Some(mut pipe) = self.stderr.take() {
+                    let _ = pipe.read_to_string(&mut stderr);
+                }
+                Some(Err(color_eyre::eyre::eyre!("{}", stderr.trim())))
+            }
+            Ok(None) => None,
+            Err(err) => Some(Err(err.into())),
+        }
+    }
 }
 
 pub struct KubectlRunnerAgent;
@@ -31,6 +63,18 @@ impl KubectlRunner for KubectlRunnerAgent {
 
         Ok(())
     }
+
+    fn spawn_background(&self, args: &[&str]) -> Result<Box<dyn BackgroundCommand>> {
+        let child = Command::new("kubectl")
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .wrap_err("Could not run commands")?;
+
+        Ok(Box::new(child))
+    }
 } 
 
 /// Custom error type for Kubernetes resource matching operations.
@@ -40,11 +84,17 @@ pub enum KubeError {
     #[error("Resource not found with provided matcher: {0} in namespace {1}")]
     ResourceNotFoundError(String, String),
     #[error("Execution not able to be performed on {0} in namespace {1}")]
-    ResourceExecutionIssue(String, String)
+    ResourceExecutionIssue(String, String),
+    /// Raised when `kubectl top` has nothing to report, usually because metrics-server isn't installed.
+    #[error("No metrics available for {0} in namespace {1}, is metrics-server installed?")]
+    MetricsUnavailable(String, String),
+    /// Raised when none of the shells to try when exec'ing exist in the pod, e.g. with a distroless image.
+    #[error("No shell found in {0} in namespace {1}, tried {2}")]
+    NoShellFound(String, String, String),
 }
 
 /// Represents a Kubernetes pod and its associated metadata.
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct FoundPod {
     /// Name of the pod.
     pub name: String,
@@ -54,6 +104,21 @@ pub struct FoundPod {
     pub deployment: String,
 }
 
+/// A row of `kubectl get pods` output for a single pod.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct PodSummary {
+    /// Name of the pod.
+    pub name: String,
+    /// Ready containers out of total containers, e.g. `1/2`.
+    pub ready: String,
+    /// Status as reported by kubectl, e.g. `Running` or `CrashLoopBackOff`.
+    pub status: String,
+    /// Restart count, including the time since the last restart if there was one.
+    pub restarts: String,
+    /// Age of the pod, e.g. `3d`.
+    pub age: String,
+}
+
 /// Attempts to find a matching Kubernetes deployment based on a matcher string and namespace.
 ///
 /// This function uses `kubectl get deployments` and regex matching to find a relevant deployment.
@@ -87,29 +152,100 @@ pub fn find_matching_deployment(runner: &dyn KubectlRunner, matcher: &str, names
     }
 }
 
-pub fn get_pod_status(runner: &dyn KubectlRunner, pod: &FoundPod) -> Result<String> {
-     // Get pod status
-    let status_regex = Regex::new(&format!(
-        r"Status:\s+[0-9A-Za-z-]+"
-    ))?;
+/// Gets the status of a pod, as `kubectl get pods` would show it.
+///
+/// # Arguments
+/// * `pod` - The pod to get the status of.
+/// * `ascii` - Whether to leave out the emoji the status is otherwise decorated with.
+///
+/// # Errors
+/// Returns an error if the pod can't be read.
+pub fn get_pod_status(runner: &dyn KubectlRunner, pod: &FoundPod, ascii: bool) -> Result<String> {
+    let pod_json = get_pod_json(runner, &pod.name, &pod.namespace)?;
 
-    let desc = describe_pod(runner, &pod)?;
-
-    match status_regex.captures(&desc) {
-        Some(matched_term) => {
-            Ok(
-            pod_status_decorator(matched_term.get(0)
-            .ok_or_else( || color_eyre::eyre::eyre!("No pod status found"))?
-            .as_str()
-            .to_string()
-            .replace("Status:", "")
-            .replace(" ", ""))
-            )
-        },
-        None => Err(KubeError::ResourceExecutionIssue(pod.name.clone(), pod.namespace.clone()).into()),
+    Ok(pod_status_decorator(pod_status(&pod_json), ascii))
+}
+
+/// Works out a pod's status from its JSON, e.g. `Running` or `CrashLoopBackOff`, without decoration.
+///
+/// # Arguments
+/// * `pod` - The pod, as in `kubectl get pod -o json` or an item of `kubectl get pods -o json`.
+pub fn pod_status(pod: &serde_json::Value) -> String {
+    // A crash looping pod is still in the Running phase, so prefer why a container isn't running, like
+    // `kubectl get pods` does. Only regular containers count: init containers have always terminated in a
+    // running pod.
+    let reasons: Vec<&str> = pod["status"]["containerStatuses"].as_array().into_iter().flatten()
+        .filter_map(|container| container["state"]["waiting"]["reason"].as_str()
+            .or_else(|| container["state"]["terminated"]["reason"].as_str()))
+        .collect();
+
+    // A container that finished cleanly, like a sidecar, says nothing about a pod that's still running.
+    if let Some(reason) = reasons.iter().find(|reason| **reason != "Completed") {
+        return reason.to_string();
+    }
+    if pod["metadata"]["deletionTimestamp"].is_string() {
+        return "Terminating".to_string();
+    }
+
+    let phase = pod["status"]["phase"].as_str().unwrap_or("Unknown");
+    if phase != "Running" && reasons.contains(&"Completed") {
+        return "Completed".to_string();
+    }
+    phase.to_string()
+}
+
+/// Works out the resource that owns a pod from its JSON, following a ReplicaSet up to its Deployment.
+///
+/// # Arguments
+/// * `pod` - The pod, as in `kubectl get pod -o json` or an item of `kubectl get pods -o json`.
+///
+/// # Returns
+/// The owner as `kind/name`, e.g. `deployment/api-server`, or `pod/<name>` for a bare pod.
+pub fn pod_owner(pod: &serde_json::Value) -> String {
+    let owner = &pod["metadata"]["ownerReferences"][0];
+
+    match (owner["kind"].as_str(), owner["name"].as_str()) {
+        // Saves looking the ReplicaSet up: a Deployment names its ReplicaSets after itself plus the hash it
+        // labels their pods with, so a ReplicaSet without one wasn't made by a Deployment.
+        (Some("ReplicaSet"), Some(replica_set)) => {
+            let deployment = pod["metadata"]["labels"]["pod-template-hash"].as_str()
+                .and_then(|hash| replica_set.strip_suffix(hash))
+                .and_then(|deployment| deployment.strip_suffix('-'));
+            match deployment {
+                Some(deployment) => format!("deployment/{deployment}"),
+                None => format!("replicaset/{replica_set}"),
+            }
+        }
+        (Some(kind), Some(owner_name)) => format!("{}/{owner_name}", kind.to_lowercase()),
+        _ => format!("pod/{}", pod["metadata"]["name"].as_str().unwrap_or_default()),
     }
 }
 
+/// Reads a pod as JSON, as `kubectl get pod -o json` prints it.
+///
+/// # Arguments
+/// * `name` - Name of the pod.
+/// * `namespace` - The Kubernetes namespace of the pod.
+///
+/// # Errors
+/// Returns `KubeError::ResourceNotFoundError` if kubectl prints nothing, e.g. as the pod has gone or can't be
+/// read, or an error if the command fails or its output isn't JSON.
+pub fn get_pod_json(runner: &dyn KubectlRunner, name: &str, namespace: &str) -> Result<serde_json::Value> {
+    let pod_json = runner.run_commands(&["get", "pod", name, "-n", namespace, "-o", "json"])?;
+    if pod_json.trim().is_empty() {
+        return Err(KubeError::ResourceNotFoundError(name.to_string(), namespace.to_string()).into());
+    }
+
+    serde_json::from_str(&pod_json).wrap_err("Could not parse pod")
+}
+
+/// Whether a status from `get_pod_status` means a container in the pod keeps crashing. Only whole reasons
+/// count, so e.g. `CreateContainerConfigError`, a problem with the pod's config, isn't a crash.
+pub fn is_crash_status(status: &str) -> bool {
+    // Skips past any emoji the status was decorated with.
+    matches!(status.split_whitespace().last(), Some("CrashLoopBackOff" | "Error" | "OOMKilled"))
+}
+
 /// Finds a pod by using a matcher string across all namespaces.
 ///
 /// # Arguments
@@ -140,7 +276,7 @@ pub fn find_matching_pod(runner: &dyn KubectlRunner, matcher: &str) -> Result<Fo
                 .as_str()
                 .to_string();
 
-            let deployment = find_matching_deployment(runner, &matcher, &ns)?;
+            let deployment = find_matching_deployment(runner, matcher, &ns)?;
 
             Ok(FoundPod {
                 name: pod,
@@ -156,43 +292,175 @@ pub fn find_matching_pod(runner: &dyn KubectlRunner, matcher: &str) -> Result<Fo
     }
 }
 
-/// Spawns a debug container into the given pod using the same image and container name.
+/// Finds every pod whose name matches a regex, across all namespaces.
 ///
 /// # Arguments
-/// * `pod` - A reference to the `FoundPod` struct representing the target pod.
+/// * `matcher` - A regex matched against pod names, e.g. `api` or `^api-.*`.
+///
+/// # Returns
+/// Each matching pod as JSON, as an item of `kubectl get pods -o json`.
 ///
 /// # Errors
-/// Returns an error if `kubectl debug` or the underlying metadata fetch commands fail.
-pub fn debug_pod(runner: &dyn KubectlRunner, pod: &FoundPod) -> Result<()> {
-    let image_name = runner.run_commands(&[
-        "get", "pod", &pod.name, "-n", &pod.namespace,
-        "-o=jsonpath={.spec.containers[0].image}",
-    ])?;
+/// Returns an error if the matcher isn't a valid regex, or the pods can't be listed.
+pub fn find_matching_pods(runner: &dyn KubectlRunner, matcher: &str) -> Result<Vec<serde_json::Value>> {
+    let re = Regex::new(matcher)?;
+
+    Ok(get_pods_json(runner, None)?.into_iter()
+        .filter(|pod| pod["metadata"]["name"].as_str().is_some_and(|name| re.is_match(name)))
+        .collect())
+}
+
+/// Where `kubectl debug` runs the debug container.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum DebugMode {
+    /// As an ephemeral container in the pod itself, sharing the target container's process namespace.
+    #[default]
+    Ephemeral,
+    /// In a copy of the pod, leaving the original untouched.
+    CopyPod,
+    /// In a new pod on the pod's node, with the node's filesystem mounted at `/host`.
+    Node,
+}
+
+impl DebugMode {
+    pub const ALL: [DebugMode; 3] = [DebugMode::Ephemeral, DebugMode::CopyPod, DebugMode::Node];
 
-    let container_name = runner.run_commands(&[
+    pub fn label(&self) -> &'static str {
+        match self {
+            DebugMode::Ephemeral => "ephemeral container",
+            DebugMode::CopyPod => "copy of the pod",
+            DebugMode::Node => "the pod's node",
+        }
+    }
+}
+
+/// How to start a debug container with `kubectl debug`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DebugOptions {
+    /// The image to debug with, e.g. `nicolaka/netshoot`. Its default command is run.
+    pub image: String,
+    /// The container whose processes to share, for ephemeral containers.
+    pub target_container: Option<String>,
+    pub mode: DebugMode,
+    /// The security profile, e.g. `general`, `netadmin` or `sysadmin`.
+    pub profile: String,
+}
+
+/// Lists the names of the pod's containers, in the order they're specified.
+///
+/// # Arguments
+/// * `pod` - The pod to list the containers of.
+///
+/// # Errors
+/// Returns an error if the `kubectl` command fails.
+pub fn get_container_names(runner: &dyn KubectlRunner, pod: &FoundPod) -> Result<Vec<String>> {
+    let names = runner.run_commands(&[
         "get", "pod", &pod.name, "-n", &pod.namespace,
-        "-o=jsonpath={.spec.containers[0].name}",
+        "-o=jsonpath={.spec.containers[*].name}",
     ])?;
 
-    runner.spawn_shell(&[
-        "debug", &pod.name, "-n", &pod.namespace, "-it",
-        &format!("--image={}", image_name),
-        &format!("--target={}", container_name),
-        "--", "sh",
-    ])
+    Ok(names.split_whitespace().map(str::to_string).collect())
+}
+
+/// Starts an interactive debug container for the pod, its copy, or its node.
+///
+/// # Arguments
+/// * `pod` - A reference to the `FoundPod` struct representing the target pod.
+/// * `options` - The image, target container, mode and profile to debug with.
+///
+/// # Errors
+/// Returns an error if `kubectl debug` fails, or the pod's node can't be found when debugging the node.
+pub fn debug_pod(runner: &dyn KubectlRunner, pod: &FoundPod, options: &DebugOptions) -> Result<()> {
+    let image = format!("--image={}", options.image);
+    let profile = format!("--profile={}", options.profile);
+    let mut args: Vec<String> = Vec::new();
+
+    match options.mode {
+        DebugMode::Ephemeral => {
+            args.push(pod.name.clone());
+            if let Some(target_container) = &options.target_container {
+                args.push(format!("--target={target_container}"));
+            }
+        }
+        DebugMode::CopyPod => {
+            args.push(pod.name.clone());
+            args.push(format!("--copy-to={}-debug", pod.name));
+            args.push("--share-processes".to_string());
+        }
+        DebugMode::Node => {
+            let node = runner.run_commands(&["get", "pod", &pod.name, "-n", &pod.namespace, "-o=jsonpath={.spec.nodeName}"])?;
+            if node.trim().is_empty() {
+                return Err(KubeError::ResourceExecutionIssue(pod.name.clone(), pod.namespace.clone()).into());
+            }
+            args.push(format!("node/{}", node.trim()));
+        }
+    }
+
+    let mut command = vec!["debug"];
+    command.extend(args.iter().map(String::as_str));
+    command.extend(["-n", &pod.namespace, "-it", &image, &profile]);
+
+    runner.spawn_shell(&command)
 }
 
-/// Starts an interactive shell session inside a running pod container.
+/// Starts an interactive shell session inside a running pod container, using the first of the given
+/// shells that exists in it.
 ///
 /// # Arguments
 /// * `pod` - A reference to the target `FoundPod`.
+/// * `shells` - Shells to try, in order of preference.
 ///
 /// # Errors
-/// Returns an error if the `kubectl exec` command fails.
-pub fn exec_into_pod(runner: &dyn KubectlRunner, pod: &FoundPod) -> Result<()> {
-    runner.spawn_shell(&[
-        "exec", "--stdin", "--tty", &pod.name, "-n", &pod.namespace, "--", "/bin/sh",
-    ])
+/// Returns `KubeError::NoShellFound` if none of the shells exist in the pod, or an error if the
+/// `kubectl exec` command fails.
+pub fn exec_into_pod(runner: &dyn KubectlRunner, pod: &FoundPod, shells: &[String]) -> Result<()> {
+    let shell = find_shell(runner, pod, shells)
+        .ok_or_else(|| KubeError::NoShellFound(pod.name.clone(), pod.namespace.clone(), shells.join(", ")))?;
+
+    exec_command(runner, pod, &[&shell])
+}
+
+/// Finds the first of the given shells that exists in the pod, by having each one echo a marker.
+///
+/// # Arguments
+/// * `pod` - The pod to look in.
+/// * `shells` - Shells to try, in order of preference.
+///
+/// # Returns
+/// The first shell that ran, or `None` if none did.
+pub fn find_shell(runner: &dyn KubectlRunner, pod: &FoundPod, shells: &[String]) -> Option<String> {
+    const MARKER: &str = "qk-shell-found";
+
+    shells.iter()
+        .find(|shell| {
+            runner.run_commands(&["exec", &pod.name, "-n", &pod.namespace, "--", shell, "-c", &format!("echo {MARKER}")])
+                .is_ok_and(|output| output.trim() == MARKER)
+        })
+        .cloned()
+}
+
+/// Splits a command typed by the user into its arguments, honouring quotes and backslash escapes like a
+/// shell does, e.g. `sh -c 'ls -la /tmp'` is `sh`, `-c` and `ls -la /tmp`.
+///
+/// # Returns
+/// The arguments, or `None` if there are none or a quote isn't closed.
+pub fn split_command(command: &str) -> Option<Vec<String>> {
+    shlex::split(command).filter(|args| !args.is_empty())
+}
+
+/// Runs a command interactively in the pod.
+///
+/// # Arguments
+/// * `pod` - The pod to run the command in.
+/// * `command` - The command and its arguments.
+///
+/// # Errors
+/// Returns an error if `kubectl exec` can't be started.
+pub fn exec_command(runner: &dyn KubectlRunner, pod: &FoundPod, command: &[&str]) -> Result<()> {
+    let mut args = vec!["exec", "--stdin", "--tty", &pod.name, "-n", &pod.namespace, "--"];
+    args.extend_from_slice(command);
+
+    runner.spawn_shell(&args)
 }
 
 /// Deletes the given pod without waiting for completion.
@@ -215,13 +483,14 @@ pub fn delete_pod(runner: &dyn KubectlRunner, pod: &FoundPod) -> Result<String>
 ///
 /// # Arguments
 /// * `pod` - A reference to the namespace's pod (only namespace field is used).
+/// * `ascii` - Leave statuses as plain text rather than marking them with emoji.
 ///
 /// # Returns
 /// A formatted string of pod statuses.
 ///
 /// # Errors
 /// Returns an error if the `kubectl` or `tac` commands fail or output can't be parsed.
-pub fn get_pods(runner: &dyn KubectlRunner, pod: &FoundPod) -> Result<String> {
+pub fn get_pods(runner: &dyn KubectlRunner, pod: &FoundPod, ascii: bool) -> Result<String> {
     let pods_output = runner.run_commands(&[
         "get", "pods", "-n", &pod.namespace,
         "--sort-by=.status.startTime", "--no-headers",
@@ -235,24 +504,427 @@ pub fn get_pods(runner: &dyn KubectlRunner, pod: &FoundPod) -> Result<String> {
 
     if let Some(stdin) = tac.stdin.as_mut() {
         stdin.write_all(pods_output.as_bytes())?;
+        // Without a final newline, tac would glue the last row onto the one before it.
+        if !pods_output.ends_with('\n') {
+            stdin.write_all(b"\n")?;
+        }
     }
 
     let output = tac.wait_with_output().wrap_err("Failed to get tac output")?;
 
-    let pods = pod_status_decorator(String::from_utf8(output.stdout)?);
+    // Decorates the status column word by word, keeping the spacing kubectl lined the columns up with.
+    let pods = String::from_utf8(output.stdout)?
+        .split('\n')
+        .map(|line| line.split(' ')
+            .map(|word| pod_status_decorator(word.to_string(), ascii))
+            .collect::<Vec<_>>()
+            .join(" "))
+        .collect::<Vec<_>>()
+        .join("\n");
        
     Ok(pods)
 }
 
-fn pod_status_decorator(status: String) -> String {
-    return status
-    .replace("Running", "🏃 Running")
-    .replace("Error", "❌ Error")
-    .replace("Completed", "✅ Completed")
-    .replace("Terminating", "💀️ Terminating")
-    .replace("CrashLoopBackOff", "🔥 CrashLoopBackOff")
-    .replace("ImagePullBackOff", "👻 ImagePullBackOff")
-    .replace("ContainerCreating", "✨️ ContainerCreating");
+/// Lists the pods in a namespace, most recently started first.
+///
+/// # Arguments
+/// * `namespace` - The Kubernetes namespace to list pods in.
+///
+/// # Returns
+/// A `PodSummary` for each pod in the namespace.
+///
+/// # Errors
+/// Returns an error if the `kubectl` command fails.
+pub fn list_pods(runner: &dyn KubectlRunner, namespace: &str) -> Result<Vec<PodSummary>> {
+    let pods_output = runner.run_commands(&[
+        "get", "pods", "-n", namespace,
+        "--sort-by=.status.startTime", "--no-headers",
+    ])?;
+
+    Ok(pods_output.lines().rev().filter_map(parse_pod_summary).collect())
+}
+
+fn parse_pod_summary(line: &str) -> Option<PodSummary> {
+    let columns: Vec<&str> = line.split_whitespace().collect();
+
+    // Restarts can span several columns, e.g. `3 (5m ago)`, so age is always taken from the end.
+    if columns.len() < 5 {
+        return None;
+    }
+
+    Some(PodSummary {
+        name: columns[0].to_string(),
+        ready: columns[1].to_string(),
+        status: columns[2].to_string(),
+        restarts: columns[3..columns.len() - 1].join(" "),
+        age: columns[columns.len() - 1].to_string(),
+    })
+}
+
+/// CPU and memory in use by a container or pod, as reported by `kubectl top`.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct ResourceUsage {
+    /// Name of the container, or of the pod for namespace-wide usage.
+    pub name: String,
+    /// CPU in use, in millicores.
+    pub cpu_millicores: u64,
+    /// Memory in use, in bytes.
+    pub memory_bytes: u64,
+}
+
+/// The CPU and memory requests and limits of a container, from the pod spec.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct ContainerResources {
+    /// Name of the container.
+    pub name: String,
+    /// CPU request, in millicores.
+    pub cpu_request: Option<u64>,
+    /// CPU limit, in millicores.
+    pub cpu_limit: Option<u64>,
+    /// Memory request, in bytes.
+    pub memory_request: Option<u64>,
+    /// Memory limit, in bytes.
+    pub memory_limit: Option<u64>,
+}
+
+/// Gets the current CPU and memory usage of each container in a pod.
+///
+/// # Arguments
+/// * `pod` - The pod to get usage for.
+///
+/// # Returns
+/// A `ResourceUsage` for each container, named after the container.
+///
+/// # Errors
+/// Returns an error if the `kubectl top` command fails or reports nothing.
+pub fn get_pod_usage(runner: &dyn KubectlRunner, pod: &FoundPod) -> Result<Vec<ResourceUsage>> {
+    let top_output = runner.run_commands(&[
+        "top", "pod", &pod.name, "-n", &pod.namespace, "--containers", "--no-headers",
+    ])?;
+
+    // Columns are POD NAME CPU(cores) MEMORY(bytes).
+    let usage: Vec<ResourceUsage> = top_output.lines()
+        .filter_map(|line| parse_resource_usage(line.split_whitespace().skip(1)))
+        .collect();
+
+    if usage.is_empty() {
+        return Err(KubeError::MetricsUnavailable(pod.name.clone(), pod.namespace.clone()).into());
+    }
+
+    Ok(usage)
+}
+
+/// Gets the current CPU and memory usage of every pod in a namespace.
+///
+/// # Arguments
+/// * `namespace` - The Kubernetes namespace to get usage for.
+///
+/// # Returns
+/// A `ResourceUsage` for each pod with metrics, named after the pod.
+///
+/// # Errors
+/// Returns an error if the `kubectl top` command fails or reports nothing.
+pub fn get_namespace_usage(runner: &dyn KubectlRunner, namespace: &str) -> Result<Vec<ResourceUsage>> {
+    let top_output = runner.run_commands(&["top", "pod", "-n", namespace, "--no-headers"])?;
+
+    // Columns are NAME CPU(cores) MEMORY(bytes).
+    let usage: Vec<ResourceUsage> = top_output.lines()
+        .filter_map(|line| parse_resource_usage(line.split_whitespace()))
+        .collect();
+
+    if usage.is_empty() {
+        return Err(KubeError::MetricsUnavailable("pods".to_string(), namespace.to_string()).into());
+    }
+
+    Ok(usage)
+}
+
+fn parse_resource_usage<'a>(mut columns: impl Iterator<Item = &'a str>) -> Option<ResourceUsage> {
+    Some(ResourceUsage {
+        name: columns.next()?.to_string(),
+        cpu_millicores: parse_cpu_quantity(columns.next()?)?,
+        memory_bytes: parse_memory_quantity(columns.next()?)?,
+    })
+}
+
+/// Reads the CPU and memory requests and limits of each container from the pod spec.
+///
+/// # Arguments
+/// * `pod` - The pod to read the spec of.
+///
+/// # Returns
+/// A `ContainerResources` for each container, with `None` for anything not set.
+///
+/// # Errors
+/// Returns an error if the `kubectl` command fails or its output isn't a pod.
+pub fn get_container_resources(runner: &dyn KubectlRunner, pod: &FoundPod) -> Result<Vec<ContainerResources>> {
+    let pod_json = get_pod_json(runner, &pod.name, &pod.namespace)?;
+
+    let containers = pod_json["spec"]["containers"].as_array()
+        .ok_or_else(|| KubeError::ResourceExecutionIssue(pod.name.clone(), pod.namespace.clone()))?;
+
+    Ok(containers.iter().map(|container| {
+        let resources = &container["resources"];
+        ContainerResources {
+            name: container["name"].as_str().unwrap_or_default().to_string(),
+            cpu_request: resources["requests"]["cpu"].as_str().and_then(parse_cpu_quantity),
+            cpu_limit: resources["limits"]["cpu"].as_str().and_then(parse_cpu_quantity),
+            memory_request: resources["requests"]["memory"].as_str().and_then(parse_memory_quantity),
+            memory_limit: resources["limits"]["memory"].as_str().and_then(parse_memory_quantity),
+        }
+    }).collect())
+}
+
+/// Parses a Kubernetes CPU quantity, e.g. `250m`, `0.5` or `2`, into millicores.
+fn parse_cpu_quantity(quantity: &str) -> Option<u64> {
+    let (number, millicores_per_unit) = match quantity.char_indices().last()? {
+        (index, 'n') => (&quantity[..index], 0.000_001),
+        (index, 'u') => (&quantity[..index], 0.001),
+        (index, 'm') => (&quantity[..index], 1.0),
+        _ => (quantity, 1000.0),
+    };
+
+    let cores: f64 = number.parse().ok()?;
+    Some((cores * millicores_per_unit).round() as u64)
+}
+
+/// Parses a Kubernetes memory quantity, e.g. `128Mi`, `1G` or `1048576`, into bytes.
+fn parse_memory_quantity(quantity: &str) -> Option<u64> {
+    const SUFFIXES: &[(&str, f64)] = &[
+        ("Ki", 1024.0), ("Mi", 1024.0 * 1024.0), ("Gi", 1024.0 * 1024.0 * 1024.0),
+        ("Ti", 1024.0 * 1024.0 * 1024.0 * 1024.0), ("Pi", 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        ("Ei", 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        ("k", 1e3), ("M", 1e6), ("G", 1e9), ("T", 1e12), ("P", 1e15), ("E", 1e18),
+        ("m", 1e-3),
+    ];
+
+    let (number, bytes_per_unit) = SUFFIXES.iter()
+        .find_map(|(suffix, bytes)| quantity.strip_suffix(suffix).map(|number| (number, *bytes)))
+        .unwrap_or((quantity, 1.0));
+
+    let amount: f64 = number.parse().ok()?;
+    Some((amount * bytes_per_unit).round() as u64)
+}
+
+/// Why a container last stopped and how often it has restarted, from the pod status.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct ContainerCrash {
+    /// Name of the container.
+    pub name: String,
+    /// Whether this is an init container.
+    pub init: bool,
+    pub restart_count: u32,
+    /// The container's current state, e.g. `Running` or `Waiting`.
+    pub state: String,
+    /// Why the container is in its current state, e.g. `CrashLoopBackOff`.
+    pub state_reason: Option<String>,
+    /// Why the container last terminated, e.g. `Error` or `OOMKilled`.
+    pub last_reason: Option<String>,
+    pub last_exit_code: Option<i64>,
+    /// When the container last terminated, as an RFC 3339 timestamp.
+    pub last_finished_at: Option<String>,
+    /// The container's memory limit as written in the spec, e.g. `128Mi`.
+    pub memory_limit: Option<String>,
+}
+
+impl ContainerCrash {
+    pub fn is_oom_killed(&self) -> bool {
+        self.last_reason.as_deref() == Some("OOMKilled") || self.state_reason.as_deref() == Some("OOMKilled")
+    }
+}
+
+/// Reads the state, last termination and restart count of each container in a pod.
+///
+/// # Arguments
+/// * `pod` - The pod to read the status of.
+///
+/// # Returns
+/// A `ContainerCrash` for each init container and container, init containers first.
+///
+/// # Errors
+/// Returns an error if the `kubectl` command fails or its output isn't a pod.
+pub fn get_container_crashes(runner: &dyn KubectlRunner, pod: &FoundPod) -> Result<Vec<ContainerCrash>> {
+    let pod_json = get_pod_json(runner, &pod.name, &pod.namespace)?;
+
+    let mut crashes = Vec::new();
+    for (statuses, specs, init) in [("initContainerStatuses", "initContainers", true), ("containerStatuses", "containers", false)] {
+        for status in pod_json["status"][statuses].as_array().into_iter().flatten() {
+            let name = status["name"].as_str().unwrap_or_default();
+            let spec = pod_json["spec"][specs].as_array().into_iter().flatten()
+                .find(|spec| spec["name"] == name);
+            // The state is an object with a single key naming it, e.g. `{"waiting": {"reason": ...}}`.
+            let (state, state_detail) = status["state"].as_object()
+                .and_then(|state| state.iter().next())
+                .map(|(state, detail)| (capitalise(state), detail))
+                .unwrap_or_else(|| ("Unknown".to_string(), &serde_json::Value::Null));
+            let last_terminated = &status["lastState"]["terminated"];
+
+            crashes.push(ContainerCrash {
+                name: name.to_string(),
+                init,
+                restart_count: status["restartCount"].as_u64().unwrap_or_default() as u32,
+                state,
+                state_reason: state_detail["reason"].as_str().map(str::to_string),
+                last_reason: last_terminated["reason"].as_str().map(str::to_string),
+                last_exit_code: last_terminated["exitCode"].as_i64(),
+                last_finished_at: last_terminated["finishedAt"].as_str().map(str::to_string),
+                memory_limit: spec.and_then(|spec| spec["resources"]["limits"]["memory"].as_str()).map(str::to_string),
+            });
+        }
+    }
+
+    Ok(crashes)
+}
+
+fn capitalise(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// How a pod is doing, from its phase and the state of its containers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PodHealth {
+    Running,
+    Pending,
+    Succeeded,
+    Failed,
+    /// Running (or trying to) with a container that keeps crashing.
+    CrashLoopBackOff,
+    Unknown,
+}
+
+impl PodHealth {
+    pub const ALL: [PodHealth; 6] = [
+        PodHealth::Running,
+        PodHealth::Pending,
+        PodHealth::Succeeded,
+        PodHealth::Failed,
+        PodHealth::CrashLoopBackOff,
+        PodHealth::Unknown,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PodHealth::Running => "Running",
+            PodHealth::Pending => "Pending",
+            PodHealth::Succeeded => "Succeeded",
+            PodHealth::Failed => "Failed",
+            PodHealth::CrashLoopBackOff => "CrashLoopBackOff",
+            PodHealth::Unknown => "Unknown",
+        }
+    }
+}
+
+/// A pod's health along with where it belongs, for summarising many pods at once.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PodPhase {
+    /// Name of the pod.
+    pub name: String,
+    /// Namespace of the pod.
+    pub namespace: String,
+    /// The pod's owner as `kind/name`, e.g. `deployment/api-server`, or `pod/<name>` for a bare pod.
+    pub owner: String,
+    pub health: PodHealth,
+}
+
+/// Gets the health of every pod in a namespace, or in every namespace.
+///
+/// # Arguments
+/// * `namespace` - The Kubernetes namespace to look in, or `None` for all namespaces.
+///
+/// # Returns
+/// A `PodPhase` for each pod.
+///
+/// # Errors
+/// Returns an error if the `kubectl` command fails or its output isn't a list of pods.
+pub fn get_pod_phases(runner: &dyn KubectlRunner, namespace: Option<&str>) -> Result<Vec<PodPhase>> {
+    Ok(get_pods_json(runner, namespace)?.iter().map(|pod| PodPhase {
+        name: pod["metadata"]["name"].as_str().unwrap_or_default().to_string(),
+        namespace: pod["metadata"]["namespace"].as_str().unwrap_or_default().to_string(),
+        owner: pod_owner(pod),
+        health: pod_health(pod),
+    }).collect())
+}
+
+/// Reads every pod in a namespace, or in every namespace, as JSON.
+///
+/// # Errors
+/// Returns an error if the `kubectl` command fails or its output isn't a list of pods.
+fn get_pods_json(runner: &dyn KubectlRunner, namespace: Option<&str>) -> Result<Vec<serde_json::Value>> {
+    let pods_json = match namespace {
+        Some(namespace) => runner.run_commands(&["get", "pods", "-n", namespace, "-o", "json"])?,
+        None => runner.run_commands(&["get", "pods", "--all-namespaces", "-o", "json"])?,
+    };
+    let mut pods_json: serde_json::Value = serde_json::from_str(&pods_json).wrap_err("Could not parse pod list")?;
+
+    match pods_json["items"].take() {
+        serde_json::Value::Array(pods) => Ok(pods),
+        _ => Err(color_eyre::eyre::eyre!("No pods in kubectl output")),
+    }
+}
+
+fn pod_health(pod: &serde_json::Value) -> PodHealth {
+    let status = &pod["status"];
+    let is_crash_looping = ["containerStatuses", "initContainerStatuses"].iter()
+        .filter_map(|statuses| status[statuses].as_array())
+        .flatten()
+        .any(|container| container["state"]["waiting"]["reason"] == "CrashLoopBackOff");
+
+    if is_crash_looping {
+        return PodHealth::CrashLoopBackOff;
+    }
+
+    match status["phase"].as_str() {
+        Some("Running") => PodHealth::Running,
+        Some("Pending") => PodHealth::Pending,
+        Some("Succeeded") => PodHealth::Succeeded,
+        Some("Failed") => PodHealth::Failed,
+        _ => PodHealth::Unknown,
+    }
+}
+
+/// Looks up a pod by its exact name and namespace.
+///
+/// # Arguments
+/// * `name` - Name of the pod.
+/// * `namespace` - The Kubernetes namespace of the pod.
+///
+/// # Returns
+/// A `FoundPod` with its owning resource in place of a matched deployment.
+///
+/// # Errors
+/// Returns an error if the pod can't be read.
+pub fn find_pod_by_name(runner: &dyn KubectlRunner, name: &str, namespace: &str) -> Result<FoundPod> {
+    let deployment = pod_owner(&get_pod_json(runner, name, namespace)?);
+
+    Ok(FoundPod {
+        name: name.to_string(),
+        namespace: namespace.to_string(),
+        deployment,
+    })
+}
+
+fn pod_status_decorator(status: String, ascii: bool) -> String {
+    // The statuses are labels enough without the emoji.
+    if ascii {
+        return status;
+    }
+
+    // Whole statuses only, so e.g. `CreateContainerConfigError` is left alone.
+    let emoji = match status.as_str() {
+        "Running" => "🏃",
+        "Error" => "❌",
+        "Completed" => "✅",
+        "Terminating" => "💀️",
+        "CrashLoopBackOff" => "🔥",
+        "ImagePullBackOff" => "👻",
+        "ContainerCreating" => "✨️",
+        _ => return status,
+    };
+
+    format!("{emoji} {status}")
 }
 
 /// Lists all resources in the pod's namespace (no headers).
@@ -321,6 +993,370 @@ pub fn get_pod_logs(runner: &dyn KubectlRunner, pod: &FoundPod, lite: bool, last
 
 }
 
+/// Lists the events involving the given pod, oldest first.
+///
+/// # Arguments
+/// * `pod` - The pod to list events for.
+///
+/// # Returns
+/// Output of `kubectl get events`.
+///
+/// # Errors
+/// Returns an error if the command fails or output is invalid.
+pub fn get_pod_events(runner: &dyn KubectlRunner, pod: &FoundPod) -> Result<String> {
+    runner.run_commands(&[
+        "get", "events", "-n", &pod.namespace,
+        &format!("--field-selector=involvedObject.name={}", pod.name),
+        "--sort-by=.lastTimestamp",
+    ])
+}
+
+/// Lists the warning events involving the given pod, oldest first.
+///
+/// # Arguments
+/// * `pod` - The pod to list warnings for.
+///
+/// # Returns
+/// Output of `kubectl get events`, without headers.
+///
+/// # Errors
+/// Returns an error if the command fails or output is invalid.
+pub fn get_pod_warnings(runner: &dyn KubectlRunner, pod: &FoundPod) -> Result<String> {
+    runner.run_commands(&[
+        "get", "events", "-n", &pod.namespace,
+        &format!("--field-selector=involvedObject.name={},type=Warning", pod.name),
+        "--sort-by=.lastTimestamp", "--no-headers",
+    ])
+}
+
+/// A file copy between the local machine and a pod.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Transfer {
+    /// Copies `remote` out of the pod to `local`.
+    Download { remote: String, local: String },
+    /// Copies `local` into the pod at `remote`.
+    Upload { local: String, remote: String },
+}
+
+/// An entry in a directory in a pod.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RemoteEntry {
+    pub name: String,
+    pub is_dir: bool,
+}
+
+/// Builds the arguments to `kubectl cp` for a transfer.
+///
+/// # Arguments
+/// * `pod` - The pod to copy to or from.
+/// * `container` - The container to copy to or from, or `None` for the pod's default container.
+/// * `transfer` - What to copy, and which way.
+pub fn cp_args(pod: &FoundPod, container: Option<&str>, transfer: &Transfer) -> Vec<String> {
+    let in_pod = |path: &str| format!("{}:{path}", pod.name);
+    let (source, destination) = match transfer {
+        Transfer::Download { remote, local } => (in_pod(remote), local.clone()),
+        Transfer::Upload { local, remote } => (local.clone(), in_pod(remote)),
+    };
+
+    let mut args = vec!["cp".to_string(), "-n".to_string(), pod.namespace.clone(), source, destination];
+    if let Some(container) = container {
+        args.extend(["-c".to_string(), container.to_string()]);
+    }
+
+    args
+}
+
+/// Runs a command in the pod and returns its output, for looking around the pod's filesystem.
+fn exec_in_container(runner: &dyn KubectlRunner, pod: &FoundPod, container: Option<&str>, command: &[&str]) -> Result<String> {
+    let mut args = vec!["exec", &pod.name, "-n", &pod.namespace];
+    if let Some(container) = container {
+        args.extend(["-c", container]);
+    }
+    args.push("--");
+    args.extend_from_slice(command);
+
+    runner.run_commands(&args)
+}
+
+/// Lists a directory in the pod with `ls`, directories first.
+///
+/// # Arguments
+/// * `pod` - The pod to look in.
+/// * `container` - The container to look in, or `None` for the pod's default container.
+/// * `path` - The directory to list.
+///
+/// # Errors
+/// Returns an error if the command fails, or lists nothing because the directory doesn't exist or the
+/// container has no `ls`.
+pub fn list_remote_dir(runner: &dyn KubectlRunner, pod: &FoundPod, container: Option<&str>, path: &str) -> Result<Vec<RemoteEntry>> {
+    // `-p` marks directories with a trailing slash, and is supported by busybox too.
+    let listing = exec_in_container(runner, pod, container, &["ls", "-1Ap", path])?;
+
+    let mut entries: Vec<RemoteEntry> = listing.lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.strip_suffix('/') {
+            Some(name) => RemoteEntry { name: name.to_string(), is_dir: true },
+            None => RemoteEntry { name: line.to_string(), is_dir: false },
+        })
+        .collect();
+
+    if entries.is_empty() {
+        return Err(KubeError::ResourceExecutionIssue(pod.name.clone(), pod.namespace.clone()).into());
+    }
+
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    Ok(entries)
+}
+
+/// Measures a file or directory in the pod with `du`.
+///
+/// # Arguments
+/// * `pod` - The pod to look in.
+/// * `container` - The container to look in, or `None` for the pod's default container.
+/// * `path` - The file or directory to measure.
+///
+/// # Returns
+/// The size in bytes, to the nearest KiB.
+///
+/// # Errors
+/// Returns an error if the command fails or its output can't be parsed.
+pub fn remote_size(runner: &dyn KubectlRunner, pod: &FoundPod, container: Option<&str>, path: &str) -> Result<u64> {
+    // busybox `du` has no `-b`, but everything has `-k`.
+    let usage = exec_in_container(runner, pod, container, &["du", "-sk", path])?;
+
+    let kibibytes: u64 = usage.split_whitespace().next()
+        .and_then(|size| size.parse().ok())
+        .ok_or_else(|| KubeError::ResourceExecutionIssue(pod.name.clone(), pod.namespace.clone()))?;
+
+    Ok(kibibytes * 1024)
+}
+
+/// Whether config comes from a ConfigMap or a Secret.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConfigKind {
+    ConfigMap,
+    Secret,
+}
+
+impl ConfigKind {
+    /// The resource type, as `kubectl` takes it.
+    pub fn resource(&self) -> &'static str {
+        match self {
+            ConfigKind::ConfigMap => "configmap",
+            ConfigKind::Secret => "secret",
+        }
+    }
+}
+
+/// Where an environment variable's value is set.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EnvSource {
+    /// Set in the pod spec.
+    Literal,
+    /// A key of a ConfigMap or Secret.
+    Key { kind: ConfigKind, name: String, key: String },
+    /// A field of the pod, e.g. `status.podIP`, only known inside the container.
+    Field(String),
+    /// A resource request or limit of the container, e.g. `limits.memory`.
+    Resource(String),
+}
+
+/// An environment variable of a container, with its value resolved where possible.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnvVar {
+    pub name: String,
+    pub source: EnvSource,
+    /// The value, or `None` if it can't be known from outside the container or its ConfigMap or Secret is missing.
+    pub value: Option<String>,
+}
+
+impl EnvVar {
+    pub fn is_secret(&self) -> bool {
+        matches!(self.source, EnvSource::Key { kind: ConfigKind::Secret, .. })
+    }
+}
+
+/// A ConfigMap or Secret mounted into a container as files.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigMount {
+    pub kind: ConfigKind,
+    pub name: String,
+    pub mount_path: String,
+}
+
+/// The environment and mounted config of a container.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ContainerEnv {
+    /// Name of the container.
+    pub name: String,
+    /// Variables set one by one, in the order they're specified.
+    pub env: Vec<EnvVar>,
+    /// ConfigMaps and Secrets whose every key is a variable, along with their variables.
+    pub env_from: Vec<(ConfigKind, String, Vec<EnvVar>)>,
+    pub mounts: Vec<ConfigMount>,
+}
+
+impl ContainerEnv {
+    /// Every ConfigMap or Secret the container refers to, whether for variables or mounts.
+    pub fn references(&self) -> Vec<(ConfigKind, String)> {
+        let keys = self.env.iter().filter_map(|var| match &var.source {
+            EnvSource::Key { kind, name, .. } => Some((*kind, name.clone())),
+            _ => None,
+        });
+        let env_from = self.env_from.iter().map(|(kind, name, _)| (*kind, name.clone()));
+        let mounts = self.mounts.iter().map(|mount| (mount.kind, mount.name.clone()));
+
+        let mut references: Vec<(ConfigKind, String)> = Vec::new();
+        for reference in keys.chain(env_from).chain(mounts) {
+            if !references.contains(&reference) {
+                references.push(reference);
+            }
+        }
+        references
+    }
+}
+
+/// Reads each container's environment variables and mounted ConfigMaps and Secrets from the pod spec,
+/// looking up values that come from ConfigMaps and Secrets.
+///
+/// # Arguments
+/// * `pod` - The pod to read the spec of.
+///
+/// # Returns
+/// A `ContainerEnv` for each container. Secret values are decoded, so mask them before showing them.
+///
+/// # Errors
+/// Returns an error if the pod can't be read. Missing ConfigMaps and Secrets leave values unresolved instead.
+pub fn get_pod_env(runner: &dyn KubectlRunner, pod: &FoundPod) -> Result<Vec<ContainerEnv>> {
+    let pod_json = get_pod_json(runner, &pod.name, &pod.namespace)?;
+
+    let containers = pod_json["spec"]["containers"].as_array()
+        .ok_or_else(|| KubeError::ResourceExecutionIssue(pod.name.clone(), pod.namespace.clone()))?;
+
+    // Volumes backed by config, by volume name.
+    let mut config_volumes: HashMap<&str, Vec<(ConfigKind, String)>> = HashMap::new();
+    for volume in pod_json["spec"]["volumes"].as_array().into_iter().flatten() {
+        let sources = std::iter::once(volume)
+            .chain(volume["projected"]["sources"].as_array().into_iter().flatten());
+        let configs = sources.filter_map(|source| {
+            if let Some(name) = source["configMap"]["name"].as_str() {
+                Some((ConfigKind::ConfigMap, name.to_string()))
+            } else {
+                source["secret"]["secretName"].as_str()
+                    .or_else(|| source["secret"]["name"].as_str())
+                    .map(|name| (ConfigKind::Secret, name.to_string()))
+            }
+        }).collect::<Vec<_>>();
+
+        if let (Some(name), false) = (volume["name"].as_str(), configs.is_empty()) {
+            config_volumes.insert(name, configs);
+        }
+    }
+
+    let mut config_data: HashMap<(ConfigKind, String), Option<BTreeMap<String, String>>> = HashMap::new();
+    let mut lookup = |kind: ConfigKind, name: &str| -> Option<BTreeMap<String, String>> {
+        config_data.entry((kind, name.to_string()))
+            .or_insert_with(|| get_config_data(runner, &pod.namespace, kind, name).ok())
+            .clone()
+    };
+
+    let mut container_envs = Vec::new();
+    for container in containers {
+        let env = container["env"].as_array().into_iter().flatten().map(|var| {
+            let name = var["name"].as_str().unwrap_or_default().to_string();
+            let value_from = &var["valueFrom"];
+            let config_key = [("configMapKeyRef", ConfigKind::ConfigMap), ("secretKeyRef", ConfigKind::Secret)].into_iter()
+                .find_map(|(reference, kind)| {
+                    let reference = &value_from[reference];
+                    Some((kind, reference["name"].as_str()?.to_string(), reference["key"].as_str()?.to_string()))
+                });
+
+            if let Some((kind, config_name, key)) = config_key {
+                let value = lookup(kind, &config_name).and_then(|data| data.get(&key).cloned());
+                EnvVar { name, source: EnvSource::Key { kind, name: config_name, key }, value }
+            } else if let Some(field) = value_from["fieldRef"]["fieldPath"].as_str() {
+                EnvVar { name, source: EnvSource::Field(field.to_string()), value: None }
+            } else if let Some(resource) = value_from["resourceFieldRef"]["resource"].as_str() {
+                EnvVar { name, source: EnvSource::Resource(resource.to_string()), value: None }
+            } else {
+                EnvVar { name, source: EnvSource::Literal, value: Some(var["value"].as_str().unwrap_or_default().to_string()) }
+            }
+        }).collect();
+
+        let env_from = container["envFrom"].as_array().into_iter().flatten().filter_map(|source| {
+            let prefix = source["prefix"].as_str().unwrap_or_default();
+            let (kind, config_name) = if let Some(name) = source["configMapRef"]["name"].as_str() {
+                (ConfigKind::ConfigMap, name.to_string())
+            } else {
+                (ConfigKind::Secret, source["secretRef"]["name"].as_str()?.to_string())
+            };
+
+            let vars = lookup(kind, &config_name).unwrap_or_default().into_iter()
+                .map(|(key, value)| EnvVar {
+                    name: format!("{prefix}{key}"),
+                    source: EnvSource::Key { kind, name: config_name.clone(), key },
+                    value: Some(value),
+                })
+                .collect();
+            Some((kind, config_name, vars))
+        }).collect();
+
+        let mounts = container["volumeMounts"].as_array().into_iter().flatten()
+            .flat_map(|mount| {
+                let mount_path = mount["mountPath"].as_str().unwrap_or_default();
+                mount["name"].as_str()
+                    .and_then(|volume| config_volumes.get(volume))
+                    .into_iter()
+                    .flatten()
+                    .map(move |(kind, name)| ConfigMount { kind: *kind, name: name.clone(), mount_path: mount_path.to_string() })
+            })
+            .collect();
+
+        container_envs.push(ContainerEnv {
+            name: container["name"].as_str().unwrap_or_default().to_string(),
+            env,
+            env_from,
+            mounts,
+        });
+    }
+
+    Ok(container_envs)
+}
+
+/// Reads the data of a ConfigMap or Secret, decoding Secret values.
+fn get_config_data(runner: &dyn KubectlRunner, namespace: &str, kind: ConfigKind, name: &str) -> Result<BTreeMap<String, String>> {
+    let config_json = runner.run_commands(&["get", kind.resource(), name, "-n", namespace, "-o", "json"])?;
+    let config_json: serde_json::Value = serde_json::from_str(&config_json).wrap_err("Could not parse config")?;
+
+    let data = config_json["data"].as_object()
+        .ok_or_else(|| KubeError::ResourceNotFoundError(name.to_string(), namespace.to_string()))?;
+
+    Ok(data.iter().map(|(key, value)| {
+        let value = value.as_str().unwrap_or_default();
+        let value = match kind {
+            ConfigKind::ConfigMap => value.to_string(),
+            ConfigKind::Secret => base64::engine::general_purpose::STANDARD.decode(value)
+                .map(|decoded| String::from_utf8_lossy(&decoded).into_owned())
+                .unwrap_or_else(|_| value.to_string()),
+        };
+        (key.clone(), value)
+    }).collect())
+}
+
+/// Opens a ConfigMap in an editor, the way `edit_deployment` does for deployments.
+///
+/// # Arguments
+/// * `pod` - The pod whose namespace the ConfigMap is in.
+/// * `name` - The name of the ConfigMap.
+///
+/// # Errors
+/// Returns an error if `kubectl edit` fails to spawn or complete.
+pub fn edit_config_map(runner: &dyn KubectlRunner, pod: &FoundPod, name: &str) -> Result<()> {
+    runner.spawn_shell(&[
+        "edit", "configmap", name, "-n", &pod.namespace,
+    ])
+}
+
 /// Describes the given pod using `kubectl describe`.
 ///
 /// # Arguments
@@ -19,6 +19,50 @@ pub struct ErroringTestKubeCtlRunner<'a> {
     expected_args: &'a [&'a str],
 }
 
+/// Replays canned output for an expected sequence of calls, without relying on the shared `COUNTER`.
+pub struct SequenceTestKubeCtlRunner<'a> {
+    calls: Vec<(&'a [&'a str], &'a str)>,
+    index: std::cell::Cell<usize>,
+}
+
+impl<'a> SequenceTestKubeCtlRunner<'a> {
+    fn new(calls: Vec<(&'a [&'a str], &'a str)>) -> Self {
+        Self { calls, index: std::cell::Cell::new(0) }
+    }
+
+    fn next_call(&self, args: &[&str]) -> &'a str {
+        let (expected_args, output) = self.calls[self.index.get()];
+        assert_eq!(args, expected_args);
+        self.index.set(self.index.get() + 1);
+        output
+    }
+}
+
+impl KubectlRunner for SequenceTestKubeCtlRunner<'_> {
+    fn run_commands(&self, args: &[&str]) -> Result<String> {
+        Ok(self.next_call(args).to_string())
+    }
+
+    fn spawn_shell(&self, args: &[&str]) -> Result<()> {
+        self.next_call(args);
+        Ok(())
+    }
+
+    fn spawn_background(&self, args: &[&str]) -> Result<Box<dyn BackgroundCommand>> {
+        self.next_call(args);
+        Ok(Box::new(FinishedTestCommand))
+    }
+}
+
+/// A background command that has already succeeded.
+pub struct FinishedTestCommand;
+
+impl BackgroundCommand for FinishedTestCommand {
+    fn try_finish(&mut self) -> Option<Result<()>> {
+        Some(Ok(()))
+    }
+}
+
 impl KubectlRunner for TestKubeCtlRunner<'_> {
     fn run_commands(&self, args: &[&str]) -> Result<String> {
         unsafe { assert_eq!(args, self.expected_args[COUNTER]) };
@@ -44,6 +88,13 @@ impl KubectlRunner for TestKubeCtlRunner<'_> {
         
         Ok(())
     }
+
+    fn spawn_background(&self, args: &[&str]) -> Result<Box<dyn BackgroundCommand>> {
+        unsafe { assert_eq!(args, self.expected_args[COUNTER]) };
+        unsafe { COUNTER += 1 };
+
+        Ok(Box::new(FinishedTestCommand))
+    }
 }
 
 impl KubectlRunner for ErroringTestKubeCtlRunner<'_> {
@@ -56,6 +107,11 @@ impl KubectlRunner for ErroringTestKubeCtlRunner<'_> {
         assert_eq!(args, self.expected_args);
         Err(eyre!(EXPECTED_ERROR))
     }
+
+    fn spawn_background(&self, args: &[&str]) -> Result<Box<dyn BackgroundCommand>> {
+        assert_eq!(args, self.expected_args);
+        Err(eyre!(EXPECTED_ERROR))
+    }
 }
 
 #[test]
@@ -64,7 +120,7 @@ fn test_find_matching_deployment_success() {
     let matcher = "api";
     let namespace = "namespace";
     let matched_result = find_matching_deployment(
-        &mut TestKubeCtlRunner {
+        &TestKubeCtlRunner {
             expected_args: vec!(&["get", "deployments,rc,rs,ds", "-n", namespace]),
             pod_output: None
         },
@@ -81,7 +137,7 @@ fn test_find_matching_deployment_failure() {
     let matcher = "goodbye";
     let namespace = "namespace";
     let matched_result = find_matching_deployment(
-        &mut TestKubeCtlRunner {
+        &TestKubeCtlRunner {
             expected_args: vec!(&["get", "deployments,rc,rs,ds", "-n", namespace]),
             pod_output: None
         },
@@ -101,7 +157,7 @@ fn test_find_matching_deployment_err() {
     let matcher = "goodbye";
     let namespace = "namespace";
     let matched_result = find_matching_deployment(
-        &mut ErroringTestKubeCtlRunner {
+        &ErroringTestKubeCtlRunner {
             expected_args: &["get", "deployments,rc,rs,ds", "-n", namespace],
         },
         matcher,
@@ -115,7 +171,7 @@ fn test_find_matching_deployment_err() {
 fn test_find_matching_pod_success() {
     unsafe { COUNTER = 0 };
     let matcher = "api-server";
-    let matched_result = find_matching_pod(&mut TestKubeCtlRunner {
+    let matched_result = find_matching_pod(&TestKubeCtlRunner {
         expected_args: vec!(&["get", "pods", "--all-namespaces"], &["get", "deployments,rc,rs,ds", "-n", "namespace"]),
         pod_output: None,
     }, matcher)
@@ -131,10 +187,9 @@ fn test_find_matching_pod_not_found() {
     unsafe { COUNTER = 0 };
     let matcher = "nonexistent";
 
-    let result = find_matching_pod(&mut TestKubeCtlRunner {
+    let result = find_matching_pod(&TestKubeCtlRunner {
         expected_args: vec!(&["get", "pods", "--all-namespaces"], &["get", "deployments,rc,rs,ds", "-n", "namespace"]),
         pod_output: Some("namespace pod-abc\nnamespace2 something-else"),
-        ..Default::default()
     }, matcher);
 
     assert!(result.is_err());
@@ -149,7 +204,7 @@ fn test_find_matching_pod_kubectl_error() {
     unsafe { COUNTER = 0 };
     let matcher = "error";
 
-    let result = find_matching_pod(&mut ErroringTestKubeCtlRunner {
+    let result = find_matching_pod(&ErroringTestKubeCtlRunner {
         expected_args: &["get", "pods", "--all-namespaces"],
     }, matcher);
 
@@ -200,43 +255,66 @@ fn test_get_pod_logs_error() {
 
 #[test]
 fn test_exec_into_pod_success() {
-    unsafe { COUNTER = 0 };
-
     let expected_pod = FoundPod {
         name: "pod".to_string(),
         namespace: "namespace".to_string(),
         deployment: "deployment".to_string()
     };
+    let shells = ["bash".to_string(), "sh".to_string()];
 
-    let args = ["exec", "--stdin", "--tty", &expected_pod.name, "-n", &expected_pod.namespace, "--", "/bin/sh"];
+    let bash_args = ["exec", "pod", "-n", "namespace", "--", "bash", "-c", "echo qk-shell-found"];
+    let sh_args = ["exec", "pod", "-n", "namespace", "--", "sh", "-c", "echo qk-shell-found"];
+    let exec_args = ["exec", "--stdin", "--tty", "pod", "-n", "namespace", "--", "sh"];
 
-    let test_kubectl_runner = TestKubeCtlRunner {
-        expected_args: vec!(&args),
-        pod_output: None,
-    };
+    let test_kubectl_runner = SequenceTestKubeCtlRunner::new(vec![
+        (&bash_args, ""),
+        (&sh_args, "qk-shell-found\n"),
+        (&exec_args, ""),
+    ]);
 
-    let result = exec_into_pod(&test_kubectl_runner, &expected_pod);
+    let result = exec_into_pod(&test_kubectl_runner, &expected_pod, &shells);
 
     assert!(result.is_ok());
 }
 
 #[test]
-fn test_exec_into_pod_failure() {
-    unsafe { COUNTER = 0 };
+fn test_exec_into_pod_no_shell() {
+    let expected_pod = FoundPod {
+        name: "pod".to_string(),
+        namespace: "namespace".to_string(),
+        deployment: "deployment".to_string()
+    };
+    let shells = ["sh".to_string()];
+
+    let args = &["exec", "pod", "-n", "namespace", "--", "sh", "-c", "echo qk-shell-found"];
+
+    let test_kubectl_runner = ErroringTestKubeCtlRunner {
+        expected_args: args
+    };
 
+    let result = exec_into_pod(&test_kubectl_runner, &expected_pod, &shells);
+
+    assert_eq!(
+        KubeError::NoShellFound("pod".to_string(), "namespace".to_string(), "sh".to_string()).to_string(),
+        result.err().unwrap().to_string()
+    );
+}
+
+#[test]
+fn test_exec_command_failure() {
     let expected_pod = FoundPod {
         name: "pod".to_string(),
         namespace: "namespace".to_string(),
         deployment: "deployment".to_string()
     };
 
-    let args = &["exec", "--stdin", "--tty", &expected_pod.name, "-n", &expected_pod.namespace, "--", "/bin/sh"];
+    let args = &["exec", "--stdin", "--tty", "pod", "-n", "namespace", "--", "python3", "-q"];
 
     let test_kubectl_runner = ErroringTestKubeCtlRunner {
         expected_args: args
     };
 
-    let result = exec_into_pod(&test_kubectl_runner, &expected_pod);
+    let result = exec_command(&test_kubectl_runner, &expected_pod, &["python3", "-q"]);
 
     assert!(result.is_err());
 }
@@ -372,104 +450,81 @@ fn test_delete_pod_failure() {
     assert!(result.is_err());
 }
 
-#[test]
-fn test_debug_pod_success() {
-    unsafe { COUNTER = 0 };
+fn debug_options(mode: DebugMode) -> DebugOptions {
+    DebugOptions {
+        image: "nicolaka/netshoot".to_string(),
+        target_container: Some("app".to_string()),
+        mode,
+        profile: "netadmin".to_string(),
+    }
+}
 
-    let pod = FoundPod {
-        name: "my-pod".to_string(),
-        namespace: "my-ns".to_string(),
-        deployment: "my-deploy".to_string(),
-    };
+#[test]
+fn test_get_container_names_success() {
+    let pod = FoundPod { name: "my-pod".to_string(), namespace: "my-ns".to_string(), deployment: "my-deploy".to_string() };
+    let args = ["get", "pod", "my-pod", "-n", "my-ns", "-o=jsonpath={.spec.containers[*].name}"];
 
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, "app sidecar")]);
 
-    let get_image = [
-            "get", "pod", &pod.name, "-n", &pod.namespace,
-            "-o=jsonpath={.spec.containers[0].image}",
-        ];
+    assert_eq!(vec!["app".to_string(), "sidecar".to_string()], get_container_names(&runner, &pod).unwrap());
+}
 
+#[test]
+fn test_debug_pod_ephemeral_success() {
+    let pod = FoundPod { name: "my-pod".to_string(), namespace: "my-ns".to_string(), deployment: "my-deploy".to_string() };
+    let debug_args = [
+        "debug", "my-pod", "--target=app", "-n", "my-ns", "-it",
+        "--image=nicolaka/netshoot", "--profile=netadmin",
+    ];
 
-    let get_container = [
-            "get", "pod", &pod.name, "-n", &pod.namespace,
-            "-o=jsonpath={.spec.containers[0].name}",
-        ];
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&debug_args, "")]);
 
-    let debug_arg = [
-            "debug", &pod.name, "-n", &pod.namespace, "-it",
-            "--image=a-fake-image",
-            "--target=a-fake-container",
-            "--", "sh",
-        ];
+    assert!(debug_pod(&runner, &pod, &debug_options(DebugMode::Ephemeral)).is_ok());
+}
 
-    let expected_calls: Vec<&[&str]> = vec![
-        &get_image,
-        &get_container,
-        &debug_arg,
+#[test]
+fn test_debug_pod_copy_success() {
+    let pod = FoundPod { name: "my-pod".to_string(), namespace: "my-ns".to_string(), deployment: "my-deploy".to_string() };
+    let debug_args = [
+        "debug", "my-pod", "--copy-to=my-pod-debug", "--share-processes", "-n", "my-ns", "-it",
+        "--image=nicolaka/netshoot", "--profile=netadmin",
     ];
 
-    struct DebugRunner<'a> {
-        calls: Vec<&'a [&'a str]>,
-    }
-
-    impl<'a> KubectlRunner for DebugRunner<'a> {
-        fn run_commands(&self, args: &[&str]) -> Result<String> {
-            unsafe {
-                assert_eq!(args, self.calls[COUNTER]);
-                COUNTER += 1;
-            }
-
-            if args.contains(&"-o=jsonpath={.spec.containers[0].image}") {
-                return Ok("a-fake-image".into());
-            }
-            if args.contains(&"-o=jsonpath={.spec.containers[0].name}") {
-                return Ok("a-fake-container".into());
-            }
-            Ok(String::new())
-        }
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&debug_args, "")]);
 
-        fn spawn_shell(&self, args: &[&str]) -> Result<()> {
-            unsafe {
-                assert_eq!(args, self.calls[COUNTER]);
-                COUNTER += 1;
-            }
-            Ok(())
-        }
-    }
+    assert!(debug_pod(&runner, &pod, &debug_options(DebugMode::CopyPod)).is_ok());
+}
 
-    let runner = DebugRunner {
-        calls: vec![
-            expected_calls[0],
-            expected_calls[1],
-            expected_calls[2],
-        ],
-    };
+#[test]
+fn test_debug_pod_node_success() {
+    let pod = FoundPod { name: "my-pod".to_string(), namespace: "my-ns".to_string(), deployment: "my-deploy".to_string() };
+    let get_node = ["get", "pod", "my-pod", "-n", "my-ns", "-o=jsonpath={.spec.nodeName}"];
+    let debug_args = [
+        "debug", "node/worker-1", "-n", "my-ns", "-it",
+        "--image=nicolaka/netshoot", "--profile=netadmin",
+    ];
 
-    let result = debug_pod(&runner, &pod);
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&get_node, "worker-1"), (&debug_args, "")]);
 
-    assert!(result.is_ok());
+    assert!(debug_pod(&runner, &pod, &debug_options(DebugMode::Node)).is_ok());
 }
 
 #[test]
 fn test_debug_pod_failure() {
-    unsafe { COUNTER = 0 };
-
     let pod = FoundPod {
         name: "bad-pod".to_string(),
         namespace: "ns".to_string(),
         deployment: "dep".to_string(),
     };
 
-    // First command should fail
-    let args = &[
-        "get", "pod", &pod.name, "-n", &pod.namespace,
-        "-o=jsonpath={.spec.containers[0].image}",
-    ];
+    // Finding the node should fail
+    let args = &["get", "pod", "bad-pod", "-n", "ns", "-o=jsonpath={.spec.nodeName}"];
 
     let runner = ErroringTestKubeCtlRunner {
         expected_args: args,
     };
 
-    let result = debug_pod(&runner, &pod);
+    let result = debug_pod(&runner, &pod, &debug_options(DebugMode::Node));
 
     assert!(result.is_err());
     assert_eq!(EXPECTED_ERROR, result.err().unwrap().to_string());
@@ -534,6 +589,10 @@ fn test_get_pods_success() {
         fn spawn_shell(&self, _args: &[&str]) -> Result<()> {
             todo!()
         }
+
+        fn spawn_background(&self, _args: &[&str]) -> Result<Box<dyn BackgroundCommand>> {
+            todo!()
+        }
     }
 
     unsafe { COUNTER = 0 };
@@ -556,7 +615,7 @@ fn test_get_pods_success() {
         pod_output: Some(sample_output),
     };
 
-    let result = get_pods(&runner, &pod).unwrap();
+    let result = get_pods(&runner, &pod, false).unwrap();
 
     // After tac: reverse order + emoji substitutions
     assert!(result.contains("🏃 Running"));
@@ -567,6 +626,26 @@ fn test_get_pods_success() {
     assert!(result.starts_with("pod-c"));
 }
 
+#[test]
+fn test_get_pods_ascii_success() {
+    let pod = FoundPod {
+        name: "ignored".to_string(),
+        namespace: "ns".to_string(),
+        deployment: "ignore".to_string(),
+    };
+
+    let kubectl_args = [
+        "get", "pods", "-n", &pod.namespace,
+        "--sort-by=.status.startTime", "--no-headers",
+    ];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&kubectl_args, "pod-a Running\npod-b Error\n")]);
+
+    let result = get_pods(&runner, &pod, true).unwrap();
+
+    assert_eq!("pod-b Error\npod-a Running", result.trim());
+}
+
 #[test]
 fn test_get_pods_failure() {
     unsafe { COUNTER = 0 };
@@ -584,7 +663,685 @@ fn test_get_pods_failure() {
 
     let runner = ErroringTestKubeCtlRunner { expected_args: args };
 
-    let result = get_pods(&runner, &pod);
+    let result = get_pods(&runner, &pod, false);
 
     assert!(result.is_err());
 }
+
+#[test]
+fn test_list_pods_success() {
+    let args = [
+        "get", "pods", "-n", "ns",
+        "--sort-by=.status.startTime", "--no-headers",
+    ];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(
+        &args,
+        "pod-a   1/1   Running   0   5d\npod-b   0/1   CrashLoopBackOff   7 (2m ago)   1h\n",
+    )]);
+
+    let result = list_pods(&runner, "ns").unwrap();
+
+    assert_eq!(2, result.len());
+    assert_eq!(PodSummary {
+        name: "pod-b".to_string(),
+        ready: "0/1".to_string(),
+        status: "CrashLoopBackOff".to_string(),
+        restarts: "7 (2m ago)".to_string(),
+        age: "1h".to_string(),
+    }, result[0]);
+    assert_eq!("pod-a", result[1].name);
+    assert_eq!("0", result[1].restarts);
+}
+
+#[test]
+fn test_list_pods_failure() {
+    let args = &[
+        "get", "pods", "-n", "ns",
+        "--sort-by=.status.startTime", "--no-headers",
+    ];
+
+    let runner = ErroringTestKubeCtlRunner { expected_args: args };
+
+    let result = list_pods(&runner, "ns");
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_find_pod_by_name_follows_replica_set_to_deployment() {
+    let get_pod = ["get", "pod", "api-7d9f8-abcde", "-n", "ns", "-o", "json"];
+    let pod_json = r#"{"metadata": {"name": "api-7d9f8-abcde", "labels": {"pod-template-hash": "7d9f8"},
+        "ownerReferences": [{"kind": "ReplicaSet", "name": "api-7d9f8"}]}}"#;
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&get_pod, pod_json)]);
+
+    let result = find_pod_by_name(&runner, "api-7d9f8-abcde", "ns").unwrap();
+
+    assert_eq!("api-7d9f8-abcde", result.name);
+    assert_eq!("ns", result.namespace);
+    assert_eq!("deployment/api", result.deployment);
+}
+
+#[test]
+fn test_find_pod_by_name_without_owner() {
+    let get_pod = ["get", "pod", "bare", "-n", "ns", "-o", "json"];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&get_pod, r#"{"metadata": {"name": "bare"}}"#)]);
+
+    let result = find_pod_by_name(&runner, "bare", "ns").unwrap();
+
+    assert_eq!("pod/bare", result.deployment);
+}
+
+#[test]
+fn test_find_pod_by_name_failure() {
+    let args = &["get", "pod", "bad", "-n", "ns", "-o", "json"];
+
+    let runner = ErroringTestKubeCtlRunner { expected_args: args };
+
+    let result = find_pod_by_name(&runner, "bad", "ns");
+
+    assert!(result.is_err());
+    assert_eq!(EXPECTED_ERROR, result.err().unwrap().to_string());
+}
+
+#[test]
+fn test_find_pod_by_name_gone() {
+    let get_pod = ["get", "pod", "gone", "-n", "ns", "-o", "json"];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&get_pod, "")]);
+
+    let result = find_pod_by_name(&runner, "gone", "ns");
+
+    assert_eq!(
+        KubeError::ResourceNotFoundError("gone".to_string(), "ns".to_string()).to_string(),
+        result.err().unwrap().to_string()
+    );
+}
+
+#[test]
+fn test_pod_owner() {
+    let pod = |metadata: &str| serde_json::from_str::<serde_json::Value>(&format!(r#"{{"metadata": {metadata}}}"#)).unwrap();
+
+    assert_eq!("deployment/api-server", pod_owner(&pod(r#"{"name": "api-server-7d9f8-abcde", "labels": {"pod-template-hash": "7d9f8"},
+        "ownerReferences": [{"kind": "ReplicaSet", "name": "api-server-7d9f8"}]}"#)));
+    assert_eq!("replicaset/standalone", pod_owner(&pod(r#"{"name": "standalone-abcde",
+        "ownerReferences": [{"kind": "ReplicaSet", "name": "standalone"}]}"#)));
+    assert_eq!("statefulset/db", pod_owner(&pod(r#"{"name": "db-0", "ownerReferences": [{"kind": "StatefulSet", "name": "db"}]}"#)));
+    assert_eq!("pod/bare", pod_owner(&pod(r#"{"name": "bare"}"#)));
+}
+
+#[test]
+fn test_get_pod_events_success() {
+    let pod = FoundPod {
+        name: "pod".to_string(),
+        namespace: "namespace".to_string(),
+        deployment: "deployment".to_string()
+    };
+
+    let args = [
+        "get", "events", "-n", "namespace",
+        "--field-selector=involvedObject.name=pod",
+        "--sort-by=.lastTimestamp",
+    ];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, "some events")]);
+
+    let result = get_pod_events(&runner, &pod);
+
+    assert!(result.is_ok());
+    assert_eq!("some events", result.unwrap())
+}
+
+#[test]
+fn test_get_pod_events_failure() {
+    let pod = FoundPod {
+        name: "pod".to_string(),
+        namespace: "namespace".to_string(),
+        deployment: "deployment".to_string()
+    };
+
+    let args = &[
+        "get", "events", "-n", "namespace",
+        "--field-selector=involvedObject.name=pod",
+        "--sort-by=.lastTimestamp",
+    ];
+
+    let runner = ErroringTestKubeCtlRunner { expected_args: args };
+
+    let result = get_pod_events(&runner, &pod);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_get_pod_usage_success() {
+    let pod = FoundPod {
+        name: "pod".to_string(),
+        namespace: "namespace".to_string(),
+        deployment: "deployment".to_string()
+    };
+
+    let args = ["top", "pod", "pod", "-n", "namespace", "--containers", "--no-headers"];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, "pod   app       12m   45Mi\npod   sidecar   1m    8Mi\n")]);
+
+    let result = get_pod_usage(&runner, &pod).unwrap();
+
+    assert_eq!(vec![
+        ResourceUsage { name: "app".to_string(), cpu_millicores: 12, memory_bytes: 45 * 1024 * 1024 },
+        ResourceUsage { name: "sidecar".to_string(), cpu_millicores: 1, memory_bytes: 8 * 1024 * 1024 },
+    ], result);
+}
+
+#[test]
+fn test_get_pod_usage_no_metrics_failure() {
+    let pod = FoundPod {
+        name: "pod".to_string(),
+        namespace: "namespace".to_string(),
+        deployment: "deployment".to_string()
+    };
+
+    let args = ["top", "pod", "pod", "-n", "namespace", "--containers", "--no-headers"];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, "")]);
+
+    let result = get_pod_usage(&runner, &pod);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_get_namespace_usage_success() {
+    let args = ["top", "pod", "-n", "namespace", "--no-headers"];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, "pod-a   250m   1Gi\npod-b   3m     12Mi\n")]);
+
+    let result = get_namespace_usage(&runner, "namespace").unwrap();
+
+    assert_eq!(vec![
+        ResourceUsage { name: "pod-a".to_string(), cpu_millicores: 250, memory_bytes: 1024 * 1024 * 1024 },
+        ResourceUsage { name: "pod-b".to_string(), cpu_millicores: 3, memory_bytes: 12 * 1024 * 1024 },
+    ], result);
+}
+
+#[test]
+fn test_get_namespace_usage_failure() {
+    let args = &["top", "pod", "-n", "namespace", "--no-headers"];
+
+    let runner = ErroringTestKubeCtlRunner { expected_args: args };
+
+    assert!(get_namespace_usage(&runner, "namespace").is_err());
+}
+
+#[test]
+fn test_get_container_resources_success() {
+    let pod = FoundPod {
+        name: "pod".to_string(),
+        namespace: "namespace".to_string(),
+        deployment: "deployment".to_string()
+    };
+
+    let args = ["get", "pod", "pod", "-n", "namespace", "-o", "json"];
+    let pod_json = r#"{"spec": {"containers": [
+        {"name": "app", "resources": {"requests": {"cpu": "100m", "memory": "64Mi"}, "limits": {"cpu": "1", "memory": "128Mi"}}},
+        {"name": "sidecar", "resources": {}}
+    ]}}"#;
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, pod_json)]);
+
+    let result = get_container_resources(&runner, &pod).unwrap();
+
+    assert_eq!(vec![
+        ContainerResources {
+            name: "app".to_string(),
+            cpu_request: Some(100),
+            cpu_limit: Some(1000),
+            memory_request: Some(64 * 1024 * 1024),
+            memory_limit: Some(128 * 1024 * 1024),
+        },
+        ContainerResources { name: "sidecar".to_string(), ..Default::default() },
+    ], result);
+}
+
+#[test]
+fn test_get_container_resources_invalid_json_failure() {
+    let pod = FoundPod {
+        name: "pod".to_string(),
+        namespace: "namespace".to_string(),
+        deployment: "deployment".to_string()
+    };
+
+    let args = ["get", "pod", "pod", "-n", "namespace", "-o", "json"];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, "not json")]);
+
+    assert!(get_container_resources(&runner, &pod).is_err());
+}
+
+#[test]
+fn test_parse_cpu_quantity() {
+    assert_eq!(Some(250), parse_cpu_quantity("250m"));
+    assert_eq!(Some(500), parse_cpu_quantity("0.5"));
+    assert_eq!(Some(2000), parse_cpu_quantity("2"));
+    assert_eq!(Some(3), parse_cpu_quantity("2500000n"));
+    assert_eq!(None, parse_cpu_quantity("lots"));
+}
+
+#[test]
+fn test_parse_memory_quantity() {
+    assert_eq!(Some(128 * 1024 * 1024), parse_memory_quantity("128Mi"));
+    assert_eq!(Some(1_000_000_000), parse_memory_quantity("1G"));
+    assert_eq!(Some(1536), parse_memory_quantity("1.5Ki"));
+    assert_eq!(Some(1048576), parse_memory_quantity("1048576"));
+    assert_eq!(None, parse_memory_quantity("plenty"));
+}
+
+#[test]
+fn test_get_pod_phases_success() {
+    let args = ["get", "pods", "-n", "namespace", "-o", "json"];
+    let pods_json = r#"{"items": [
+        {"metadata": {"name": "api-7d9f8-abcde", "namespace": "namespace", "labels": {"pod-template-hash": "7d9f8"},
+            "ownerReferences": [{"kind": "ReplicaSet", "name": "api-7d9f8"}]},
+         "status": {"phase": "Running", "containerStatuses": [{"state": {"running": {}}}]}},
+        {"metadata": {"name": "api-7d9f8-fghij", "namespace": "namespace", "labels": {"pod-template-hash": "7d9f8"},
+            "ownerReferences": [{"kind": "ReplicaSet", "name": "api-7d9f8"}]},
+         "status": {"phase": "Running", "containerStatuses": [{"state": {"waiting": {"reason": "CrashLoopBackOff"}}}]}},
+        {"metadata": {"name": "migrate-xyz", "namespace": "namespace", "ownerReferences": [{"kind": "Job", "name": "migrate"}]},
+         "status": {"phase": "Succeeded"}},
+        {"metadata": {"name": "scratch", "namespace": "namespace"},
+         "status": {"phase": "Pending"}},
+        {"metadata": {"name": "lost", "namespace": "namespace"},
+         "status": {}}
+    ]}"#;
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, pods_json)]);
+
+    let result = get_pod_phases(&runner, Some("namespace")).unwrap();
+
+    let summary: Vec<(&str, &str, PodHealth)> = result.iter()
+        .map(|pod| (pod.name.as_str(), pod.owner.as_str(), pod.health))
+        .collect();
+    assert_eq!(vec![
+        ("api-7d9f8-abcde", "deployment/api", PodHealth::Running),
+        ("api-7d9f8-fghij", "deployment/api", PodHealth::CrashLoopBackOff),
+        ("migrate-xyz", "job/migrate", PodHealth::Succeeded),
+        ("scratch", "pod/scratch", PodHealth::Pending),
+        ("lost", "pod/lost", PodHealth::Unknown),
+    ], summary);
+    assert!(result.iter().all(|pod| pod.namespace == "namespace"));
+}
+
+#[test]
+fn test_get_pod_phases_all_namespaces_success() {
+    let args = ["get", "pods", "--all-namespaces", "-o", "json"];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, r#"{"items": []}"#)]);
+
+    assert_eq!(Vec::<PodPhase>::new(), get_pod_phases(&runner, None).unwrap());
+}
+
+#[test]
+fn test_find_matching_pods_success() {
+    let args = ["get", "pods", "--all-namespaces", "-o", "json"];
+    let pods_json = r#"{"items": [
+        {"metadata": {"name": "api-7d9f8-abcde", "namespace": "prod", "labels": {"pod-template-hash": "7d9f8"},
+            "ownerReferences": [{"kind": "ReplicaSet", "name": "api-7d9f8"}]},
+         "status": {"phase": "Running"}},
+        {"metadata": {"name": "web-5c6b7-fghij", "namespace": "prod", "labels": {"pod-template-hash": "5c6b7"},
+            "ownerReferences": [{"kind": "ReplicaSet", "name": "web-5c6b7"}]},
+         "status": {"phase": "Running"}},
+        {"metadata": {"name": "api-migrate-xyz", "namespace": "jobs", "ownerReferences": [{"kind": "Job", "name": "api-migrate"}]},
+         "status": {"phase": "Succeeded"}}
+    ]}"#;
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, pods_json)]);
+
+    let result = find_matching_pods(&runner, "^api").unwrap();
+
+    let summary: Vec<(&str, &str, String)> = result.iter()
+        .map(|pod| (pod["metadata"]["name"].as_str().unwrap(), pod["metadata"]["namespace"].as_str().unwrap(), pod_owner(pod)))
+        .collect();
+    assert_eq!(vec![
+        ("api-7d9f8-abcde", "prod", "deployment/api".to_string()),
+        ("api-migrate-xyz", "jobs", "job/api-migrate".to_string()),
+    ], summary);
+}
+
+#[test]
+fn test_find_matching_pods_invalid_matcher() {
+    let runner = SequenceTestKubeCtlRunner::new(vec![]);
+
+    assert!(find_matching_pods(&runner, "api(").is_err());
+}
+
+#[test]
+fn test_get_pod_phases_failure() {
+    let args = &["get", "pods", "-n", "namespace", "-o", "json"];
+
+    let runner = ErroringTestKubeCtlRunner { expected_args: args };
+
+    assert!(get_pod_phases(&runner, Some("namespace")).is_err());
+}
+
+#[test]
+fn test_get_pod_status_prefers_container_reason() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let args = ["get", "pod", "pod", "-n", "namespace", "-o", "json"];
+    let pod_json = r#"{"status": {"phase": "Running", "containerStatuses": [
+        {"name": "app", "state": {"waiting": {"reason": "CrashLoopBackOff"}}, "lastState": {"terminated": {"reason": "OOMKilled"}}}
+    ]}}"#;
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, pod_json)]);
+
+    assert_eq!("CrashLoopBackOff", get_pod_status(&runner, &pod, true).unwrap());
+}
+
+#[test]
+fn test_get_pod_status_uses_phase_when_running() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let args = ["get", "pod", "pod", "-n", "namespace", "-o", "json"];
+    let pod_json = r#"{"status": {"phase": "Running", "containerStatuses": [
+        {"name": "app", "state": {"running": {}}, "lastState": {"terminated": {"reason": "Error"}}}
+    ]}}"#;
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, pod_json)]);
+
+    assert_eq!("🏃 Running", get_pod_status(&runner, &pod, false).unwrap());
+}
+
+#[test]
+fn test_get_pod_status_ignores_init_containers() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let args = ["get", "pod", "pod", "-n", "namespace", "-o", "json"];
+    let pod_json = r#"{"status": {"phase": "Running",
+        "initContainerStatuses": [{"name": "migrate", "state": {"terminated": {"reason": "Completed", "exitCode": 0}}}],
+        "containerStatuses": [
+            {"name": "app", "state": {"running": {}}},
+            {"name": "sidecar", "state": {"terminated": {"reason": "Completed"}}}
+        ]}}"#;
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, pod_json)]);
+
+    assert_eq!("Running", get_pod_status(&runner, &pod, true).unwrap());
+}
+
+#[test]
+fn test_get_pod_status_completed_when_finished() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let args = ["get", "pod", "pod", "-n", "namespace", "-o", "json"];
+    let pod_json = r#"{"status": {"phase": "Succeeded",
+        "initContainerStatuses": [{"name": "migrate", "state": {"terminated": {"reason": "Completed"}}}],
+        "containerStatuses": [{"name": "job", "state": {"terminated": {"reason": "Completed"}}}]}}"#;
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, pod_json)]);
+
+    assert_eq!("Completed", get_pod_status(&runner, &pod, true).unwrap());
+}
+
+#[test]
+fn test_get_pod_status_terminating() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let args = ["get", "pod", "pod", "-n", "namespace", "-o", "json"];
+    let pod_json = r#"{"metadata": {"deletionTimestamp": "2026-10-19T09:00:00Z"},
+        "status": {"phase": "Running", "containerStatuses": [{"name": "app", "state": {"running": {}}}]}}"#;
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, pod_json)]);
+
+    assert_eq!("Terminating", get_pod_status(&runner, &pod, true).unwrap());
+}
+
+#[test]
+fn test_split_command() {
+    assert_eq!(Some(vec!["sh".to_string(), "-c".to_string(), "ls -la /tmp".to_string()]), split_command("sh -c 'ls -la /tmp'"));
+    assert_eq!(Some(vec!["echo".to_string(), "a b".to_string(), "\"c\"".to_string()]), split_command(r#"echo a\ b "\"c\"""#));
+    assert_eq!(None, split_command("sh -c 'ls"));
+    assert_eq!(None, split_command("   "));
+}
+
+#[test]
+fn test_is_crash_status() {
+    assert!(is_crash_status("🔥 CrashLoopBackOff"));
+    assert!(is_crash_status("OOMKilled"));
+    assert!(!is_crash_status("🏃 Running"));
+    assert!(!is_crash_status("Completed"));
+    assert!(!is_crash_status("CreateContainerConfigError"));
+    assert!(!is_crash_status("ErrImagePull"));
+}
+
+#[test]
+fn test_get_pod_status_decorates_whole_reasons_only() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let args = ["get", "pod", "pod", "-n", "namespace", "-o", "json"];
+    let pod_json = r#"{"status": {"phase": "Pending", "containerStatuses": [
+        {"name": "app", "state": {"waiting": {"reason": "CreateContainerConfigError"}}}
+    ]}}"#;
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, pod_json)]);
+
+    assert_eq!("CreateContainerConfigError", get_pod_status(&runner, &pod, false).unwrap());
+}
+
+#[test]
+fn test_get_container_crashes_success() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let args = ["get", "pod", "pod", "-n", "namespace", "-o", "json"];
+    let pod_json = r#"{
+        "spec": {
+            "initContainers": [{"name": "init"}],
+            "containers": [{"name": "app", "resources": {"limits": {"memory": "128Mi"}}}]
+        },
+        "status": {
+            "initContainerStatuses": [{"name": "init", "restartCount": 0, "state": {"terminated": {"reason": "Completed", "exitCode": 0}}}],
+            "containerStatuses": [{
+                "name": "app",
+                "restartCount": 7,
+                "state": {"waiting": {"reason": "CrashLoopBackOff"}},
+                "lastState": {"terminated": {"reason": "OOMKilled", "exitCode": 137, "finishedAt": "2024-05-01T10:00:00Z"}}
+            }]
+        }
+    }"#;
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, pod_json)]);
+
+    let result = get_container_crashes(&runner, &pod).unwrap();
+
+    assert_eq!(vec![
+        ContainerCrash {
+            name: "init".to_string(),
+            init: true,
+            state: "Terminated".to_string(),
+            state_reason: Some("Completed".to_string()),
+            ..Default::default()
+        },
+        ContainerCrash {
+            name: "app".to_string(),
+            init: false,
+            restart_count: 7,
+            state: "Waiting".to_string(),
+            state_reason: Some("CrashLoopBackOff".to_string()),
+            last_reason: Some("OOMKilled".to_string()),
+            last_exit_code: Some(137),
+            last_finished_at: Some("2024-05-01T10:00:00Z".to_string()),
+            memory_limit: Some("128Mi".to_string()),
+        },
+    ], result);
+    assert!(result[1].is_oom_killed());
+    assert!(!result[0].is_oom_killed());
+}
+
+#[test]
+fn test_get_container_crashes_failure() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let args = &["get", "pod", "pod", "-n", "namespace", "-o", "json"];
+
+    let runner = ErroringTestKubeCtlRunner { expected_args: args };
+
+    assert!(get_container_crashes(&runner, &pod).is_err());
+}
+
+#[test]
+fn test_get_pod_warnings_success() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let args = [
+        "get", "events", "-n", "namespace",
+        "--field-selector=involvedObject.name=pod,type=Warning",
+        "--sort-by=.lastTimestamp", "--no-headers",
+    ];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, "2m Warning BackOff pod/pod Back-off restarting failed container")]);
+
+    assert_eq!("2m Warning BackOff pod/pod Back-off restarting failed container", get_pod_warnings(&runner, &pod).unwrap());
+}
+
+#[test]
+fn test_cp_args_download() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let transfer = Transfer::Download { remote: "/tmp/heap.hprof".to_string(), local: "./heap.hprof".to_string() };
+
+    assert_eq!(
+        vec!["cp", "-n", "namespace", "pod:/tmp/heap.hprof", "./heap.hprof"],
+        cp_args(&pod, None, &transfer),
+    );
+}
+
+#[test]
+fn test_cp_args_upload_to_container() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let transfer = Transfer::Upload { local: "app.yaml".to_string(), remote: "/etc/app.yaml".to_string() };
+
+    assert_eq!(
+        vec!["cp", "-n", "namespace", "app.yaml", "pod:/etc/app.yaml", "-c", "sidecar"],
+        cp_args(&pod, Some("sidecar"), &transfer),
+    );
+}
+
+#[test]
+fn test_list_remote_dir_success() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let args = ["exec", "pod", "-n", "namespace", "-c", "app", "--", "ls", "-1Ap", "/tmp"];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, "heap.hprof\nlogs/\n.cache/\napp.yaml\n")]);
+
+    assert_eq!(vec![
+        RemoteEntry { name: ".cache".to_string(), is_dir: true },
+        RemoteEntry { name: "logs".to_string(), is_dir: true },
+        RemoteEntry { name: "app.yaml".to_string(), is_dir: false },
+        RemoteEntry { name: "heap.hprof".to_string(), is_dir: false },
+    ], list_remote_dir(&runner, &pod, Some("app"), "/tmp").unwrap());
+}
+
+#[test]
+fn test_list_remote_dir_empty_failure() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let args = ["exec", "pod", "-n", "namespace", "--", "ls", "-1Ap", "/nope"];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, "")]);
+
+    assert!(list_remote_dir(&runner, &pod, None, "/nope").is_err());
+}
+
+#[test]
+fn test_remote_size_success() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let args = ["exec", "pod", "-n", "namespace", "--", "du", "-sk", "/tmp/heap.hprof"];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, "2048\t/tmp/heap.hprof\n")]);
+
+    assert_eq!(2048 * 1024, remote_size(&runner, &pod, None, "/tmp/heap.hprof").unwrap());
+}
+
+#[test]
+fn test_remote_size_failure() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let args = &["exec", "pod", "-n", "namespace", "--", "du", "-sk", "/tmp"];
+
+    let runner = ErroringTestKubeCtlRunner { expected_args: args };
+
+    assert!(remote_size(&runner, &pod, None, "/tmp").is_err());
+}
+
+#[test]
+fn test_get_pod_env_success() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let pod_args = ["get", "pod", "pod", "-n", "namespace", "-o", "json"];
+    let pod_json = r#"{"spec": {
+        "containers": [{
+            "name": "app",
+            "env": [
+                {"name": "LOG_LEVEL", "value": "debug"},
+                {"name": "DB_HOST", "valueFrom": {"configMapKeyRef": {"name": "app-config", "key": "db-host"}}},
+                {"name": "DB_PASSWORD", "valueFrom": {"secretKeyRef": {"name": "db-creds", "key": "password"}}},
+                {"name": "POD_IP", "valueFrom": {"fieldRef": {"fieldPath": "status.podIP"}}},
+                {"name": "MISSING", "valueFrom": {"configMapKeyRef": {"name": "app-config", "key": "nope"}}}
+            ],
+            "envFrom": [{"configMapRef": {"name": "app-config"}, "prefix": "APP_"}],
+            "volumeMounts": [
+                {"name": "config", "mountPath": "/etc/app"},
+                {"name": "tls", "mountPath": "/etc/tls"},
+                {"name": "data", "mountPath": "/data"}
+            ]
+        }],
+        "volumes": [
+            {"name": "config", "configMap": {"name": "app-config"}},
+            {"name": "tls", "secret": {"secretName": "tls-cert"}},
+            {"name": "data", "emptyDir": {}}
+        ]
+    }}"#;
+    let config_map_args = ["get", "configmap", "app-config", "-n", "namespace", "-o", "json"];
+    let secret_args = ["get", "secret", "db-creds", "-n", "namespace", "-o", "json"];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![
+        (&pod_args, pod_json),
+        (&config_map_args, r#"{"data": {"db-host": "db.internal"}}"#),
+        (&secret_args, r#"{"data": {"password": "aHVudGVyMg=="}}"#),
+    ]);
+
+    let result = get_pod_env(&runner, &pod).unwrap();
+
+    let config_key = |kind, name: &str, key: &str| EnvSource::Key { kind, name: name.to_string(), key: key.to_string() };
+    assert_eq!(vec![ContainerEnv {
+        name: "app".to_string(),
+        env: vec![
+            EnvVar { name: "LOG_LEVEL".to_string(), source: EnvSource::Literal, value: Some("debug".to_string()) },
+            EnvVar { name: "DB_HOST".to_string(), source: config_key(ConfigKind::ConfigMap, "app-config", "db-host"), value: Some("db.internal".to_string()) },
+            EnvVar { name: "DB_PASSWORD".to_string(), source: config_key(ConfigKind::Secret, "db-creds", "password"), value: Some("hunter2".to_string()) },
+            EnvVar { name: "POD_IP".to_string(), source: EnvSource::Field("status.podIP".to_string()), value: None },
+            EnvVar { name: "MISSING".to_string(), source: config_key(ConfigKind::ConfigMap, "app-config", "nope"), value: None },
+        ],
+        env_from: vec![(ConfigKind::ConfigMap, "app-config".to_string(), vec![
+            EnvVar { name: "APP_db-host".to_string(), source: config_key(ConfigKind::ConfigMap, "app-config", "db-host"), value: Some("db.internal".to_string()) },
+        ])],
+        mounts: vec![
+            ConfigMount { kind: ConfigKind::ConfigMap, name: "app-config".to_string(), mount_path: "/etc/app".to_string() },
+            ConfigMount { kind: ConfigKind::Secret, name: "tls-cert".to_string(), mount_path: "/etc/tls".to_string() },
+        ],
+    }], result);
+    assert!(result[0].env[2].is_secret());
+    assert_eq!(vec![
+        (ConfigKind::ConfigMap, "app-config".to_string()),
+        (ConfigKind::Secret, "db-creds".to_string()),
+        (ConfigKind::Secret, "tls-cert".to_string()),
+    ], result[0].references());
+}
+
+#[test]
+fn test_get_pod_env_failure() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let args = &["get", "pod", "pod", "-n", "namespace", "-o", "json"];
+
+    let runner = ErroringTestKubeCtlRunner { expected_args: args };
+
+    assert!(get_pod_env(&runner, &pod).is_err());
+}
+
+#[test]
+fn test_edit_config_map_success() {
+    let pod = FoundPod { name: "pod".to_string(), namespace: "namespace".to_string(), deployment: "deployment".to_string() };
+    let args = ["edit", "configmap", "app-config", "-n", "namespace"];
+
+    let runner = SequenceTestKubeCtlRunner::new(vec![(&args, "")]);
+
+    assert!(edit_config_map(&runner, &pod, "app-config").is_ok());
+}
@@ -2,22 +2,70 @@ mod kubectl;
 mod cli;
 mod gui;
 mod updater;
+mod settings;
+
+use std::path::PathBuf;
 
 use color_eyre::{config::HookBuilder, eyre::{Error, Result}};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
+use crate::cli::output::{self, OutputFormat};
 use crate::kubectl::KubectlRunnerAgent;
 
 /// Program to execute kubectl commands on resources, using regex matching.
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
+#[command(version, about, long_about = None, args_conflicts_with_subcommands = true, disable_version_flag = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Commands>,
     #[arg(short, long, help="--update to download and install the newest version of Quick-Kit", conflicts_with="matcher")]
     update: bool,
+    #[arg(long, help="Check for a newer version of Quick-Kit without installing it", conflicts_with_all=["matcher", "update"])]
+    check_update: bool,
+    #[arg(short='V', long, value_name="TAG", num_args=0..=1, help="Print version, or with --update the release to install, e.g. v0.1.9")]
+    version: Option<Option<String>>,
+    #[arg(long, help="Go back to the version of Quick-Kit replaced by the last update", conflicts_with_all=["matcher", "update"])]
+    rollback: bool,
+    #[arg(long, value_name="DIR", help="Install into, or roll back in, DIR instead of the running qk")]
+    install_dir: Option<PathBuf>,
     #[arg(index = 1, help="my-pod-matcher, e.g. 'nginx' for 'nginx-controller-abc123-abc'")]
     matcher: Option<String>,
 }
 
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Copy files to or from a matching pod, e.g. `qk cp api:/tmp/heap.hprof ./heap.hprof`
+    Cp {
+        #[arg(help="Where to copy from, a local path or <matcher>:/path in a pod")]
+        source: String,
+        #[arg(help="Where to copy to, a local path or <matcher>:/path in a pod")]
+        destination: String,
+        #[arg(short, long, help="The container to copy to or from, instead of the pod's default container")]
+        container: Option<String>,
+    },
+    /// Print the pod qk would open for a matcher as pod/<name>, or with its namespace using -o json
+    Match {
+        #[arg(help="my-pod-matcher, e.g. 'nginx' for 'nginx-controller-abc123-abc'")]
+        matcher: String,
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Name, help="How to print the pod")]
+        output: OutputFormat,
+    },
+    /// Print the status of the pod qk would open for a matcher
+    Status {
+        #[arg(help="my-pod-matcher, e.g. 'nginx' for 'nginx-controller-abc123-abc'")]
+        matcher: String,
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table, help="How to print the pod")]
+        output: OutputFormat,
+    },
+    /// List every pod whose name matches a regex, in all namespaces
+    List {
+        #[arg(help="A regex for pod names, e.g. '^api-'")]
+        matcher: String,
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table, help="How to print the pods")]
+        output: OutputFormat,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     HookBuilder::default()
@@ -27,14 +75,54 @@ async fn main() -> Result<(), Error> {
     .install()?;
 
     let args = Args::parse();
+    let settings = match settings::load() {
+        Ok(settings) => settings,
+        // A broken config shouldn't stop qk updating itself, which might be how the config gets fixed.
+        Err(err) if args.update || args.check_update || args.rollback => {
+            eprintln!("Ignoring invalid config: {err}");
+            settings::Settings::default()
+        }
+        Err(err) => return Err(err),
+    };
+
+    if args.check_update {
+        updater::check_latest(&settings.update).await?
+    }
+
+    match &args.version {
+        Some(tag) if args.update => updater::update(&settings.update, args.install_dir.as_deref(), tag.as_deref()).await?,
+        Some(None) => {
+            println!("qk {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        Some(Some(_)) => return Err(color_eyre::eyre::eyre!("--version with a release tag needs --update")),
+        None if args.update => updater::update(&settings.update, args.install_dir.as_deref(), None).await?,
+        None => {}
+    }
+
+    if args.rollback {
+        updater::rollback(args.install_dir.as_deref())?
+    }
 
-    if args.update {
-        updater::download_latest().await?
+    match args.command {
+        Some(Commands::Cp { source, destination, container }) => {
+            let (matcher, transfer) = cli::parse_transfer(&source, &destination)?;
+            let pod = kubectl::find_matching_pod(&KubectlRunnerAgent{}, &matcher)?;
+            cli::copy(&KubectlRunnerAgent{}, &pod, container.as_deref(), transfer)?
+        }
+        Some(Commands::Match { matcher, output }) | Some(Commands::Status { matcher, output }) => {
+            println!("{}", output::format_pod(&output::match_report(&KubectlRunnerAgent{}, &matcher)?, output)?);
+        }
+        Some(Commands::List { matcher, output }) => {
+            println!("{}", output::format_pods(&output::list_reports(&KubectlRunnerAgent{}, &matcher)?, output)?);
+        }
+        None => {}
     }
 
     if let Some(matcher_string) = args.matcher {
         let pod = kubectl::find_matching_pod(&KubectlRunnerAgent{}, matcher_string.as_str())?;
-        gui::gui(pod)?
+        let newer_release = updater::spawn_check(&settings.update)?;
+        gui::gui(pod, &settings, newer_release)?
     }
 
     Ok(())
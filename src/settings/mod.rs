@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+
+use color_eyre::eyre::{Result};
+use config::{Config, Environment, File};
+use serde::Deserialize;
+
+/// User settings for qk, read from `~/.config/qk/config.{toml,yaml,json}` and overridden by `QK_*`
+/// environment variables (e.g. `QK_MOUSE=true`).
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    /// Whether the TUI captures the mouse for scrolling, clicking and selecting text.
+    /// Off by default, which keeps the terminal's own text selection.
+    pub mouse: bool,
+    /// Where copied text goes.
+    pub clipboard: ClipboardMode,
+    /// The theme to draw the TUI with: `dark`, `light`, `high-contrast`, or one defined under `themes`.
+    pub theme: String,
+    /// Swap emoji for plain text labels, for terminals that draw emoji at the wrong width.
+    pub ascii: bool,
+    /// Custom themes, by name.
+    pub themes: HashMap<String, CustomTheme>,
+    /// Shells to try when exec'ing into a pod, in order of preference. The first one found in the
+    /// container is used.
+    pub shells: Vec<String>,
+    /// Images offered when debugging a pod, first one selected.
+    pub debug_images: Vec<String>,
+    /// Where `--update` gets new versions of qk from.
+    pub update: UpdateSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            mouse: false,
+            clipboard: ClipboardMode::default(),
+            theme: "dark".to_string(),
+            ascii: false,
+            themes: HashMap::new(),
+            shells: vec!["bash".to_string(), "sh".to_string(), "ash".to_string()],
+            debug_images: vec!["nicolaka/netshoot".to_string(), "busybox".to_string()],
+            update: UpdateSettings::default(),
+        }
+    }
+}
+
+/// A theme defined in the config, as a built-in theme with some of its colours replaced.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct CustomTheme {
+    /// The built-in theme to start from, `dark` if not set.
+    pub base: Option<String>,
+    /// Colours to replace, by name, e.g. `highlight = "#ff8700"`.
+    #[serde(flatten)]
+    pub colors: HashMap<String, String>,
+}
+
+/// Where updates come from, for networks that can't reach GitHub directly.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct UpdateSettings {
+    /// A GitHub style releases API to use instead of qk's own GitHub releases.
+    pub releases_url: Option<String>,
+    /// A plain directory of releases, at a URL or on disk, used instead of any releases API.
+    pub mirror: Option<String>,
+    /// The proxy to download through, instead of the one in `HTTPS_PROXY`.
+    pub proxy: Option<String>,
+    /// A token to authenticate with, e.g. to get around GitHub's rate limit.
+    pub token: Option<String>,
+    /// Whether to look for a new release when the TUI starts, at most once a day.
+    pub background_check: bool,
+}
+
+/// How text copied in the TUI reaches the clipboard.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardMode {
+    /// Ask the terminal to set the clipboard with the OSC 52 escape sequence, falling back to a file for
+    /// copies too big for it.
+    #[default]
+    Osc52,
+    /// Always write copied text to a file.
+    File,
+}
+
+/// Finds the directory qk's config lives in, honouring `$XDG_CONFIG_HOME`.
+///
+/// # Returns
+///
+/// * `Some(PathBuf)` - `$XDG_CONFIG_HOME/qk`, or `~/.config/qk` when that isn't set.
+/// * `None` - If neither `$XDG_CONFIG_HOME` nor `$HOME` is set.
+pub fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("qk"))
+}
+
+/// Loads the user's settings, falling back to the defaults for anything not configured.
+///
+/// # Errors
+///
+/// Returns an error if the config file or an environment variable can't be parsed.
+pub fn load() -> Result<Settings> {
+    load_from(config_dir().map(|dir| dir.join("config")), None)
+}
+
+/// Loads settings from a config file (without its extension, which may be any format the `config`
+/// crate supports), then applies `QK_*` environment variables on top. Lists are comma separated in the
+/// environment, e.g. `QK_SHELLS=zsh,sh`.
+///
+/// # Arguments
+///
+/// * `file` - The config file to read, if there is one.
+/// * `environment` - The variables to apply instead of the process environment, or `None` for the
+///   process environment.
+fn load_from(file: Option<PathBuf>, environment: Option<HashMap<String, String>>) -> Result<Settings> {
+    let mut builder = Config::builder();
+
+    if let Some(file) = file {
+        builder = builder.add_source(File::with_name(&file.to_string_lossy()).required(false));
+    }
+
+    let settings = builder
+        .add_source(Environment::with_prefix("QK").prefix_separator("_").separator("__").try_parsing(true)
+            .list_separator(",").with_list_parse_key("shells").with_list_parse_key("debug_images")
+            .source(environment))
+        .build()?
+        .try_deserialize()?;
+
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests;
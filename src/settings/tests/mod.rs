@@ -0,0 +1,95 @@
+use super::*;
+
+use std::fs;
+
+use tempfile::TempDir;
+
+/// A config directory of its own, so tests running in parallel don't read each other's files.
+fn config_dir_with(contents: &str) -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("config.toml"), contents).unwrap();
+    dir
+}
+
+/// No `QK_*` variables, whatever the environment the tests run in has set.
+fn no_environment() -> Option<HashMap<String, String>> {
+    Some(HashMap::new())
+}
+
+#[test]
+fn test_load_from_missing_file_uses_defaults() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let settings = load_from(Some(dir.path().join("config")), no_environment()).unwrap();
+
+    assert_eq!(Settings::default(), settings);
+    assert!(!settings.mouse);
+}
+
+#[test]
+fn test_load_from_file_success() {
+    let dir = config_dir_with("mouse = true\nclipboard = \"file\"\n");
+
+    let settings = load_from(Some(dir.path().join("config")), no_environment()).unwrap();
+
+    assert!(settings.mouse);
+    assert_eq!(ClipboardMode::File, settings.clipboard);
+}
+
+#[test]
+fn test_load_from_invalid_file_failure() {
+    let dir = config_dir_with("mouse = \"sometimes\"\n");
+
+    assert!(load_from(Some(dir.path().join("config")), no_environment()).is_err());
+}
+
+#[test]
+fn test_load_from_file_custom_theme_success() {
+    let dir = config_dir_with("theme = \"mine\"\nascii = true\n\n[themes.mine]\nbase = \"light\"\nhighlight = \"#FF8700\"\n");
+
+    let settings = load_from(Some(dir.path().join("config")), no_environment()).unwrap();
+
+    assert_eq!("mine", settings.theme);
+    assert!(settings.ascii);
+    let theme = &settings.themes["mine"];
+    assert_eq!(Some("light".to_string()), theme.base);
+    assert_eq!(HashMap::from([("highlight".to_string(), "#FF8700".to_string())]), theme.colors);
+}
+
+#[test]
+fn test_load_from_file_shells_success() {
+    let dir = config_dir_with("shells = [\"zsh\", \"/busybox/sh\"]\n");
+
+    let settings = load_from(Some(dir.path().join("config")), no_environment()).unwrap();
+
+    assert_eq!(vec!["zsh".to_string(), "/busybox/sh".to_string()], settings.shells);
+}
+
+#[test]
+fn test_load_from_file_update_success() {
+    let dir = config_dir_with("[update]\nmirror = \"https://mirror.internal/qk\"\nproxy = \"http://proxy.internal:3128\"\n");
+
+    let settings = load_from(Some(dir.path().join("config")), no_environment()).unwrap();
+
+    assert_eq!(Some("https://mirror.internal/qk".to_string()), settings.update.mirror);
+    assert_eq!(Some("http://proxy.internal:3128".to_string()), settings.update.proxy);
+    assert_eq!(None, settings.update.token);
+}
+
+#[test]
+fn test_load_from_environment_overrides_file() {
+    let dir = config_dir_with("mouse = false\ntheme = \"light\"\n");
+    let environment = HashMap::from([
+        ("QK_MOUSE".to_string(), "true".to_string()),
+        ("QK_SHELLS".to_string(), "zsh,sh".to_string()),
+        ("QK_UPDATE__TOKEN".to_string(), "secret".to_string()),
+        ("OTHER_MOUSE".to_string(), "false".to_string()),
+    ]);
+
+    let settings = load_from(Some(dir.path().join("config")), Some(environment)).unwrap();
+
+    assert!(settings.mouse);
+    assert_eq!("light", settings.theme);
+    assert_eq!(vec!["zsh".to_string(), "sh".to_string()], settings.shells);
+    assert_eq!(Some("secret".to_string()), settings.update.token);
+}
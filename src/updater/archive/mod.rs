@@ -0,0 +1,113 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path};
+
+use color_eyre::eyre::Result;
+use flate2::read::GzDecoder;
+use tar::{Archive, EntryType};
+use zip::ZipArchive;
+
+use super::{install, UpdateError, BINARY_NAME};
+
+/// How a release asset is packaged.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AssetKind {
+    Zip,
+    TarGz,
+    /// The binary itself.
+    Binary,
+}
+
+/// Works out how an asset is packaged from its first bytes. An asset is only taken to be the binary itself
+/// if its name doesn't say it's an archive.
+///
+/// # Errors
+///
+/// Returns `UpdateError::NotAnArchive` if the asset is named like an archive but its first bytes aren't
+/// a zip's or a tarball's, e.g. an HTML error page saved under the asset's name.
+pub fn detect(asset_name: &str, header: &[u8]) -> Result<AssetKind> {
+    let name = asset_name.to_lowercase();
+    let named_archive = [".zip", ".tar.gz", ".tgz"].iter().any(|extension| name.ends_with(extension));
+
+    if header.starts_with(b"PK\x03\x04") {
+        Ok(AssetKind::Zip)
+    } else if header.starts_with(&[0x1f, 0x8b]) {
+        Ok(AssetKind::TarGz)
+    } else if named_archive {
+        Err(UpdateError::NotAnArchive(asset_name.to_string()).into())
+    } else {
+        Ok(AssetKind::Binary)
+    }
+}
+
+/// Whether an archive entry's path stays inside the directory it would be extracted to.
+fn is_enclosed(path: &Path) -> bool {
+    path.components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+fn is_binary(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == BINARY_NAME)
+}
+
+/// Installs the qk binary from a downloaded asset, whether it's a zip, a tarball or the binary itself.
+/// Only the binary is taken from an archive, anything else in it (e.g. a README) is left alone.
+///
+/// # Arguments
+///
+/// * `asset` - The downloaded asset.
+/// * `asset_name` - The asset's name, which must agree with its contents if it says it's an archive.
+/// * `destination` - Where to install the binary.
+///
+/// # Errors
+///
+/// Returns `UpdateError::UnsafeArchivePath` if any entry in an archive would be extracted outside of it,
+/// `UpdateError::BinaryNotInArchive` if there's no qk binary in it, `UpdateError::NotAnArchive` if it's
+/// named like an archive but isn't one, or an error if it can't be read or installed.
+pub fn install_asset(mut asset: File, asset_name: &str, destination: &Path) -> Result<()> {
+    let mut header = Vec::with_capacity(4);
+    asset.by_ref().take(4).read_to_end(&mut header)?;
+    asset.seek(SeekFrom::Start(0))?;
+
+    match detect(asset_name, &header)? {
+        AssetKind::Zip => {
+            let mut archive = ZipArchive::new(asset)?;
+            let mut binary_index = None;
+            for index in 0..archive.len() {
+                let entry = archive.by_index(index)?;
+                if entry.enclosed_name().is_none_or(|path| !is_enclosed(path)) {
+                    return Err(UpdateError::UnsafeArchivePath(entry.name().to_string()).into());
+                }
+                if entry.is_file() && entry.enclosed_name().is_some_and(is_binary) {
+                    binary_index.get_or_insert(index);
+                }
+            }
+
+            let binary_index = binary_index.ok_or_else(|| UpdateError::BinaryNotInArchive(BINARY_NAME.to_string()))?;
+            install(&mut archive.by_index(binary_index)?, destination)?;
+            Ok(())
+        }
+        AssetKind::TarGz => {
+            // Checked in a first pass, as a tarball can only be read through once.
+            for entry in Archive::new(GzDecoder::new(asset.try_clone()?)).entries()? {
+                let path = entry?.path()?.into_owned();
+                if !is_enclosed(&path) {
+                    return Err(UpdateError::UnsafeArchivePath(path.display().to_string()).into());
+                }
+            }
+
+            asset.seek(SeekFrom::Start(0))?;
+            for entry in Archive::new(GzDecoder::new(asset)).entries()? {
+                let mut entry = entry?;
+                if entry.header().entry_type() == EntryType::Regular && is_binary(&entry.path()?) {
+                    return install(&mut entry, destination);
+                }
+            }
+
+            Err(UpdateError::BinaryNotInArchive(BINARY_NAME.to_string()).into())
+        }
+        AssetKind::Binary => install(&mut asset, destination),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,132 @@
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::PathBuf;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use tar::{Builder, Header};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use super::*;
+
+/// A fresh directory for a test to install into.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("qk-archive-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn asset_file(bytes: &[u8]) -> File {
+    let mut file = tempfile::tempfile().unwrap();
+    file.write_all(bytes).unwrap();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file
+}
+
+fn zip_of(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, contents) in entries {
+        zip.start_file(*name, FileOptions::default()).unwrap();
+        zip.write_all(contents.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+/// A tarball of the entries, with their paths written as is, so they can point outside the archive.
+fn tar_gz_of(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut builder = Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (name, contents) in entries {
+        let mut header = Header::new_gnu();
+        header.as_gnu_mut().unwrap().name[..name.len()].copy_from_slice(name.as_bytes());
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o755);
+        header.set_entry_type(EntryType::Regular);
+        header.set_cksum();
+        builder.append(&header, contents.as_bytes()).unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+#[test]
+fn test_detect() {
+    assert_eq!(AssetKind::Zip, detect("qk-x86_64-unknown-linux-gnu", b"PK\x03\x04").unwrap());
+    assert_eq!(AssetKind::TarGz, detect("qk-x86_64-unknown-linux-gnu.zip", &[0x1f, 0x8b, 0x08, 0x00]).unwrap());
+    assert_eq!(AssetKind::Binary, detect("qk-x86_64-unknown-linux-gnu", b"\x7fELF").unwrap());
+}
+
+#[test]
+fn test_detect_archive_name_with_other_contents_failure() {
+    for (name, header) in [("qk-x86_64-unknown-linux-gnu.zip", &b"<htm"[..]), ("qk-x86_64-unknown-linux-gnu.TAR.GZ", b"\x7fELF"), ("qk.tgz", b"")] {
+        assert_eq!(
+            UpdateError::NotAnArchive(name.to_string()).to_string(),
+            detect(name, header).unwrap_err().to_string()
+        );
+    }
+}
+
+#[test]
+fn test_install_asset_zip_takes_only_binary() {
+    let dir = test_dir("zip");
+    let asset = zip_of(&[("qk/README.md", "# qk"), (&format!("qk/{BINARY_NAME}"), "new binary"), ("qk/LICENSE", "MIT")]);
+
+    install_asset(asset_file(&asset), "qk.zip", &dir.join(BINARY_NAME)).unwrap();
+
+    assert_eq!("new binary", fs::read_to_string(dir.join(BINARY_NAME)).unwrap());
+    assert_eq!(1, fs::read_dir(&dir).unwrap().count());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_install_asset_tar_gz_takes_only_binary() {
+    let dir = test_dir("tar-gz");
+    let asset = tar_gz_of(&[("README.md", "# qk"), (&format!("./{BINARY_NAME}"), "new binary")]);
+
+    install_asset(asset_file(&asset), "qk.tar.gz", &dir.join(BINARY_NAME)).unwrap();
+
+    assert_eq!("new binary", fs::read_to_string(dir.join(BINARY_NAME)).unwrap());
+    assert_eq!(1, fs::read_dir(&dir).unwrap().count());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_install_asset_raw_binary() {
+    let dir = test_dir("binary");
+
+    install_asset(asset_file(b"\x7fELF new binary"), "qk-x86_64-unknown-linux-gnu", &dir.join(BINARY_NAME)).unwrap();
+
+    assert_eq!("\x7fELF new binary", fs::read_to_string(dir.join(BINARY_NAME)).unwrap());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_install_asset_zip_path_traversal() {
+    let dir = test_dir("zip-traversal");
+    let asset = zip_of(&[(&format!("../../{BINARY_NAME}"), "evil binary")]);
+
+    let err = install_asset(asset_file(&asset), "qk.zip", &dir.join(BINARY_NAME)).unwrap_err();
+
+    assert!(matches!(err.downcast_ref::<UpdateError>(), Some(UpdateError::UnsafeArchivePath(..))));
+    assert!(!dir.join(BINARY_NAME).exists());
+}
+
+#[test]
+fn test_install_asset_tar_gz_path_traversal() {
+    let dir = test_dir("tar-traversal");
+    let asset = tar_gz_of(&[(&format!("../{BINARY_NAME}"), "evil binary"), (BINARY_NAME, "new binary")]);
+
+    let err = install_asset(asset_file(&asset), "qk.tar.gz", &dir.join(BINARY_NAME)).unwrap_err();
+
+    assert_eq!(format!("Release archive entry ../{BINARY_NAME} points outside the archive, refusing to install it"), err.to_string());
+    assert!(!dir.join(BINARY_NAME).exists());
+}
+
+#[test]
+fn test_install_asset_no_binary() {
+    let dir = test_dir("no-binary");
+    let asset = zip_of(&[("README.md", "# qk")]);
+
+    let err = install_asset(asset_file(&asset), "qk.zip", &dir.join(BINARY_NAME)).unwrap_err();
+
+    assert!(matches!(err.downcast_ref::<UpdateError>(), Some(UpdateError::BinaryNotInArchive(..))));
+}
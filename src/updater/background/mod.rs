@@ -0,0 +1,104 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{self, UpdateSettings};
+
+use super::source::{self, Retries, Source};
+use super::{is_newer, CURRENT_VERSION};
+
+/// How long to go between looking for a new release.
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Where the last check is remembered, in the config dir.
+const CACHE_FILE: &str = "update-check.json";
+
+/// The last background check, so releases are looked for at most once per `CHECK_INTERVAL`.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+struct CheckCache {
+    /// When the check was made, in seconds since the Unix epoch.
+    checked_at: u64,
+    /// The latest release's tag, if the check found one.
+    latest: Option<String>,
+}
+
+fn seconds_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Finds the latest release, unless it was looked for recently, in which case the one found then is used.
+/// A failed check counts as a check, so being offline doesn't mean trying on every start.
+///
+/// # Arguments
+///
+/// * `client` - The client to fetch the release with.
+/// * `source` - Where to look for releases.
+/// * `cache` - Where the last check is remembered.
+/// * `now` - The current time.
+///
+/// # Returns
+///
+/// The latest release's tag if it's newer than the running version.
+async fn newer_release(client: &Client, source: &Source, cache: &Path, now: SystemTime) -> Option<String> {
+    let previous: CheckCache = fs::read_to_string(cache).ok()
+        .and_then(|cached| serde_json::from_str(&cached).ok())
+        .unwrap_or_default();
+
+    let latest = if seconds_since_epoch(now).saturating_sub(previous.checked_at) < CHECK_INTERVAL.as_secs() {
+        previous.latest
+    } else {
+        // Quiet, as anything written now would land on top of the TUI.
+        let retries = Retries { quiet: true, ..Retries::default() };
+        let latest = source.fetch_release(client, None, retries).await.ok().map(|release| release.tag_name).or(previous.latest);
+        let checked = CheckCache { checked_at: seconds_since_epoch(now), latest: latest.clone() };
+        if let Some(dir) = cache.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let _ = serde_json::to_string(&checked).map(|checked| fs::write(cache, checked));
+        latest
+    };
+
+    latest.filter(|latest| is_newer(latest, CURRENT_VERSION).unwrap_or(false))
+}
+
+fn cache_path() -> Option<PathBuf> {
+    settings::config_dir().map(|dir| dir.join(CACHE_FILE))
+}
+
+/// Starts looking for a newer release in the background, if the user has opted in, so the TUI can start
+/// straight away and mention the release once it's found.
+///
+/// # Arguments
+///
+/// * `settings` - Whether to check, and where to look for releases.
+///
+/// # Returns
+///
+/// A receiver for the newer release's tag, sent only if there is one, or `None` if not checking.
+///
+/// # Errors
+///
+/// Returns an error if the update settings aren't valid.
+pub fn spawn_check(settings: &UpdateSettings) -> Result<Option<Receiver<String>>> {
+    let Some(cache) = cache_path().filter(|_| settings.background_check) else {
+        return Ok(None);
+    };
+
+    let client = source::client(settings)?;
+    let source = Source::from_settings(settings);
+    let (sender, receiver) = mpsc::channel();
+    tokio::spawn(async move {
+        if let Some(latest) = newer_release(&client, &source, &cache, SystemTime::now()).await {
+            let _ = sender.send(latest);
+        }
+    });
+
+    Ok(Some(receiver))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,63 @@
+use super::*;
+
+/// A fresh directory with a mirror whose latest release is v99.0.0, and somewhere to cache checks.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("qk-background-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("mirror/v99.0.0")).unwrap();
+    fs::write(dir.join("mirror/LATEST"), "v99.0.0").unwrap();
+    fs::write(dir.join("mirror/v99.0.0/SHA256SUMS"), "aaaa  qk-x86_64-unknown-linux-gnu.zip\n").unwrap();
+    dir
+}
+
+fn mirror(dir: &Path) -> Source {
+    Source::Mirror(dir.join("mirror").display().to_string())
+}
+
+fn read_cache(cache: &Path) -> CheckCache {
+    serde_json::from_str(&fs::read_to_string(cache).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_newer_release_checks_when_stale() {
+    let dir = test_dir("stale");
+    let cache = dir.join("config/update-check.json");
+    let now = UNIX_EPOCH + Duration::from_secs(1_760_000_000);
+
+    assert_eq!(Some("v99.0.0".to_string()), newer_release(&Client::new(), &mirror(&dir), &cache, now).await);
+    assert_eq!(CheckCache { checked_at: 1_760_000_000, latest: Some("v99.0.0".to_string()) }, read_cache(&cache));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_newer_release_uses_recent_check() {
+    let dir = test_dir("recent");
+    let cache = dir.join("update-check.json");
+    fs::write(&cache, r#"{"checked_at":1760000000,"latest":"v98.0.0"}"#).unwrap();
+    let now = UNIX_EPOCH + Duration::from_secs(1_760_000_000) + CHECK_INTERVAL - Duration::from_secs(1);
+
+    // The mirror's newer release isn't seen until the next day's check.
+    assert_eq!(Some("v98.0.0".to_string()), newer_release(&Client::new(), &mirror(&dir), &cache, now).await);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_newer_release_up_to_date() {
+    let dir = test_dir("up-to-date");
+    let cache = dir.join("update-check.json");
+    fs::write(&cache, format!(r#"{{"checked_at":1760000000,"latest":"v{CURRENT_VERSION}"}}"#)).unwrap();
+
+    assert_eq!(None, newer_release(&Client::new(), &mirror(&dir), &cache, UNIX_EPOCH + Duration::from_secs(1_760_000_000)).await);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_newer_release_failed_check_counts() {
+    let dir = test_dir("offline");
+    let cache = dir.join("update-check.json");
+    let now = UNIX_EPOCH + Duration::from_secs(1_760_000_000);
+
+    assert_eq!(None, newer_release(&Client::new(), &Source::Mirror(dir.join("missing").display().to_string()), &cache, now).await);
+    assert_eq!(CheckCache { checked_at: 1_760_000_000, latest: None }, read_cache(&cache));
+    fs::remove_dir_all(dir).unwrap();
+}
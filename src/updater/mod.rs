@@ -1,79 +1,379 @@
-use std::{fs::File, io::Write};
+use std::{fs::{self, File}, io::{self, Read}, path::{Path, PathBuf}};
 
 use color_eyre::eyre::{Error, Result};
-use reqwest::{Client};
-use serde::Deserialize;
-use reqwest::header::USER_AGENT;
-use zip::ZipArchive;
+use reqwest::Client;
+use semver::Version;
+use thiserror::Error;
 
+use crate::settings::UpdateSettings;
+use source::{download, ReleaseMeta, Retries, Source};
 
-#[derive(Deserialize)]
+mod archive;
+mod background;
+mod source;
 
-struct Asset {
-    browser_download_url: String
+pub use background::spawn_check;
+mod verify;
+
+/// Errors raised while finding and installing a release.
+#[derive(Error, Debug)]
+pub enum UpdateError {
+    /// Raised when a release has nothing built for the platform qk is running on.
+    #[error("No release asset found for {0}, available assets: {1}")]
+    NoMatchingAsset(String, String),
+    /// Raised when the new binary can't be written next to the one it replaces, e.g. a root owned `/usr/local/bin`.
+    #[error("Can't write to {0}, re-run with permission to write there or pass --install-dir")]
+    InstallDirNotWritable(String),
+    /// Raised when a release archive doesn't contain a qk binary.
+    #[error("No {0} binary found in the release archive")]
+    BinaryNotInArchive(String),
+    /// Raised when an entry in a release archive would be extracted outside of it, e.g. `../../.bashrc`.
+    #[error("Release archive entry {0} points outside the archive, refusing to install it")]
+    UnsafeArchivePath(String),
+    /// Raised when an asset is named like an archive, e.g. `qk.zip`, but its contents aren't one.
+    #[error("{0} is named like an archive but its contents aren't one, refusing to install it")]
+    NotAnArchive(String),
+    /// Raised when a release's tag isn't a semantic version, so it can't be compared with this one.
+    #[error("Release tag {0} isn't a version, expected e.g. v1.2.3")]
+    InvalidVersion(String),
+    /// Raised when a release doesn't publish a SHA-256 checksum for the asset to install.
+    #[error("No published checksum for {0}, refusing to install it")]
+    ChecksumMissing(String),
+    /// Raised when the downloaded asset doesn't match its published checksum.
+    #[error("Checksum mismatch for {0}, expected {1} but downloaded {2}, refusing to install it")]
+    ChecksumMismatch(String, String, String),
+    /// Raised when qk was built with a signing key but the release isn't signed with it.
+    #[error("Invalid signature for {0}: {1}, refusing to install it")]
+    InvalidSignature(String, String),
+    /// Raised when asked to install a release that doesn't exist.
+    #[error("No release tagged {0}")]
+    ReleaseNotFound(String),
+    /// Raised when rolling back without a previous version to go back to.
+    #[error("No previous version to roll back to at {0}")]
+    NoBackup(String),
 }
 
-#[derive(Deserialize)]
-struct LatestReleaseMeta {
-    assets: Vec<Asset>
+/// The name of qk's executable on this platform.
+const BINARY_NAME: &str = if cfg!(windows) { "qk.exe" } else { "qk" };
+
+/// The platform qk was built for, which release assets are named after, e.g. `qk-x86_64-unknown-linux-gnu.zip`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Target {
+    /// As in `std::env::consts::OS`, e.g. `linux` or `macos`.
+    pub os: String,
+    /// As in `std::env::consts::ARCH`, e.g. `x86_64` or `aarch64`.
+    pub arch: String,
+    /// The C library on Linux, `gnu` or `musl`. Empty elsewhere.
+    pub libc: String,
 }
 
+impl Target {
+    /// The platform of the running binary.
+    pub fn current() -> Self {
+        let libc = if cfg!(target_env = "musl") { "musl" } else if cfg!(target_env = "gnu") { "gnu" } else { "" };
+        Target { os: std::env::consts::OS.to_string(), arch: std::env::consts::ARCH.to_string(), libc: libc.to_string() }
+    }
+
+    /// The Rust target triple, e.g. `x86_64-unknown-linux-gnu`.
+    pub fn triple(&self) -> String {
+        match self.os.as_str() {
+            "linux" => format!("{}-unknown-linux-{}", self.arch, self.libc),
+            "macos" => format!("{}-apple-darwin", self.arch),
+            "windows" => format!("{}-pc-windows-{}", self.arch, if self.libc == "gnu" { "gnu" } else { "msvc" }),
+            os => format!("{}-unknown-{os}", self.arch),
+        }
+    }
+
+    /// Scores how well an asset's name matches the platform, or `None` if it's built for another one.
+    /// The exact triple scores highest. A static musl binary runs on a glibc system too, so it's taken
+    /// if there's nothing built for glibc.
+    fn score(&self, asset_name: &str) -> Option<u8> {
+        let name = words(asset_name);
+        let triple = words(&self.triple());
+        if name.windows(triple.len()).any(|window| window == triple) {
+            return Some(3);
+        }
+
+        // Whole words only, so e.g. `arm` doesn't match `arm64`.
+        let has_any = |aliases: &[&str]| aliases.iter().any(|alias| name.iter().any(|word| word == alias));
+        let arch_aliases: &[&str] = match self.arch.as_str() {
+            "x86_64" => &["x86_64", "amd64", "x64"],
+            "aarch64" => &["aarch64", "arm64"],
+            "x86" => &["i686", "i386", "x86"],
+            "arm" => &["arm", "armv7", "armhf"],
+            arch => &[arch],
+        };
+        let os_aliases: &[&str] = match self.os.as_str() {
+            "macos" => &["darwin", "macos", "apple"],
+            "windows" => &["windows", "win64", "win32"],
+            os => &[os],
+        };
+        if !has_any(arch_aliases) || !has_any(os_aliases) {
+            return None;
+        }
+
+        match (self.libc.as_str(), name.iter().any(|word| word.starts_with("musl"))) {
+            ("musl", false) if self.os == "linux" => None,
+            ("gnu", true) => Some(1),
+            _ => Some(2),
+        }
+    }
+}
+
+/// Splits an asset name into lowercase words at `-`, `_` and `.`. `x86_64` stays one word despite its
+/// underscore, and `x86-64` is read as another way of writing it.
+fn words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for word in name.to_lowercase().split(['-', '_', '.']).filter(|word| !word.is_empty()) {
+        match words.last_mut() {
+            Some(last) if word == "64" && last == "x86" => last.push_str("_64"),
+            _ => words.push(word.to_string()),
+        }
+    }
+    words
+}
+
+/// Picks the release asset built for a platform.
+///
+/// # Arguments
+///
+/// * `assets` - The names of a release's assets.
+/// * `target` - The platform to pick for.
+///
+/// # Returns
 ///
-/// Downloads latest version of QuickKit available for installation.
-pub async fn download_latest() -> Result<(), Error> {
-    
-    let releases_url = "https://api.github.com/repos/jamesgiu/quick-kit/releases/latest";
+/// The index of the best matching asset.
+///
+/// # Errors
+///
+/// Returns `UpdateError::NoMatchingAsset`, listing the assets, if none of them are built for the platform.
+pub fn select_asset(assets: &[&str], target: &Target) -> Result<usize> {
+    assets.iter().enumerate()
+        .filter(|(_, name)| !verify::is_verification_file(name))
+        .filter_map(|(index, name)| target.score(name).map(|score| (score, index)))
+        // The earliest of the best matches, as `max_by_key` would take the last.
+        .min_by_key(|(score, index)| (u8::MAX - score, *index))
+        .map(|(_, index)| index)
+        .ok_or_else(|| UpdateError::NoMatchingAsset(target.triple(), assets.join(", ")).into())
+}
 
-    let client = Client::new();
+/// The version of qk that's running.
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// The minisign public key releases are signed with, if release builds were given one. Signatures are
+/// checked only when it's set.
+const PUBLIC_KEY: Option<&str> = option_env!("QK_MINISIGN_PUBLIC_KEY");
 
-    let response = client
-    .get(releases_url)
-    .header(USER_AGENT, "quick-kit")
-    .send()
-    .await?
-    .json::<LatestReleaseMeta>()
-    .await?;
+/// Parses a release tag as a version, with or without a leading `v`.
+///
+/// # Errors
+///
+/// Returns `UpdateError::InvalidVersion` if the tag isn't a semantic version.
+pub fn parse_version(tag: &str) -> Result<Version> {
+    Version::parse(tag.trim().trim_start_matches('v')).map_err(|_| UpdateError::InvalidVersion(tag.to_string()).into())
+}
 
+/// Whether a release is newer than the given version.
+///
+/// # Arguments
+///
+/// * `tag` - The release's tag, e.g. `v0.2.0`.
+/// * `current` - The version to compare against, e.g. `0.1.10`.
+///
+/// # Errors
+///
+/// Returns `UpdateError::InvalidVersion` if either isn't a semantic version.
+pub fn is_newer(tag: &str, current: &str) -> Result<bool> {
+    Ok(parse_version(tag)? > parse_version(current)?)
+}
 
-    let download_url = &response.assets[0].browser_download_url;
+/// Lets the user know about a newer release, with its release notes.
+fn print_release_notes(release: &ReleaseMeta) {
+    println!("qk {} is available, you have v{CURRENT_VERSION}.", release.tag_name);
+    if let Some(notes) = release.body.as_deref().map(str::trim).filter(|notes| !notes.is_empty()) {
+        println!("\nWhat's new in {}:\n{notes}\n", release.tag_name);
+    }
+}
 
-    let download_bytes = client
-    .get(download_url)
-    .header(USER_AGENT, "quick-kit")
-    .send()
-    .await?
-    .bytes()
-    .await?;
+/// Checks whether there's a newer version of qk than the one running, without installing it.
+///
+/// # Arguments
+///
+/// * `settings` - Where to look for releases, and how to reach it.
+pub async fn check_latest(settings: &UpdateSettings) -> Result<(), Error> {
+    let release = Source::from_settings(settings).fetch_release(&source::client(settings)?, None, Retries::default()).await?;
 
-    let temp_path = "/tmp/qk.zip";
-    let mut zip_file_write = File::create(&temp_path)?;
-    zip_file_write.write_all(&download_bytes)?;
+    if is_newer(&release.tag_name, CURRENT_VERSION)? {
+        print_release_notes(&release);
+        println!("Run qk --update to install it.");
+    } else {
+        println!("qk v{CURRENT_VERSION} is up to date.");
+    }
 
-    println!("Download complete.");
+    Ok(())
+}
 
-    // Extract
-    let zip_file_read = File::open(temp_path)?;
-    let mut archive = ZipArchive::new(zip_file_read)?;
-    archive.extract("/home/jjg/.local/bin/")?;
+///
+/// Downloads latest version of QuickKit available for installation, unless it's already running, or the
+/// given version.
+///
+/// # Arguments
+///
+/// * `settings` - Where to look for releases, and how to reach it.
+/// * `install_dir` - The directory to install qk into, instead of replacing the running executable.
+/// * `tag` - The release to install, e.g. `v0.1.9`, which may be older than the running version.
+pub async fn update(settings: &UpdateSettings, install_dir: Option<&Path>, tag: Option<&str>) -> Result<(), Error> {
+    update_from(&source::client(settings)?, &Source::from_settings(settings), PUBLIC_KEY, install_dir, tag).await
+}
 
-    println!("Extraction complete.");
+/// Installs the latest or a tagged release from a source, once it's been checked against its checksum
+/// and, given a public key, its signature.
+async fn update_from(client: &Client, source: &Source, public_key: Option<&str>, install_dir: Option<&Path>, tag: Option<&str>) -> Result<()> {
+    // Worked out first, so nothing is downloaded if it can't be found.
+    let destination = install_path(install_dir)?;
 
-    
+    let response = source.fetch_release(client, tag, Retries::default()).await?;
 
-    
+    if tag.is_some() {
+        if parse_version(&response.tag_name)? == parse_version(CURRENT_VERSION)? {
+            println!("qk v{CURRENT_VERSION} is already running.");
+            return Ok(());
+        }
+        println!("Installing qk {}, you have v{CURRENT_VERSION}.", response.tag_name);
+    } else if !is_newer(&response.tag_name, CURRENT_VERSION)? {
+        println!("qk v{CURRENT_VERSION} is up to date.");
+        return Ok(());
+    } else {
+        print_release_notes(&response);
+    }
 
-    // let releases_json = reqwest::get(releases_url)
-    // .await?
-    // .json::<LatestReleaseMeta>()
-    // .await?;
+    let asset_names: Vec<&str> = response.assets.iter().map(|asset| asset.name.as_str()).collect();
+    let asset = &response.assets[select_asset(&asset_names, &Target::current())?];
+    let download_file = source::download_to_file(client, &asset.browser_download_url, Retries::default()).await?;
 
-    // let releases_json = reqwest::get(releases_url).await?.text().await?;
+    let checksum_asset = verify::checksum_asset(&asset.name, &asset_names)
+        .map(|index| &response.assets[index])
+        .ok_or_else(|| UpdateError::ChecksumMissing(asset.name.clone()))?;
+    let checksums = String::from_utf8_lossy(&download(client, &checksum_asset.browser_download_url, Retries::default()).await?).into_owned();
+    verify::verify_checksum(&mut download_file.reopen()?, &checksums, &asset.name)?;
 
-    // println!("{:?}", releases_json);
-    // FIXME error with line
-    println!("{:?}", response.assets[0].browser_download_url);
+    if let Some(public_key) = public_key {
+        let signature_name = format!("{}.minisig", asset.name);
+        let signature_asset = response.assets.iter().find(|candidate| candidate.name == signature_name)
+            .ok_or_else(|| UpdateError::InvalidSignature(asset.name.clone(), format!("{signature_name} wasn't published")))?;
+        let signature = String::from_utf8_lossy(&download(client, &signature_asset.browser_download_url, Retries::default()).await?).into_owned();
+        verify::verify_signature(&mut download_file.reopen()?, &signature, public_key, &asset.name)?;
+    }
 
+    println!("Download complete and verified.");
+
+    archive::install_asset(download_file.reopen()?, &asset.name, &destination)?;
+
+    println!("Installed qk {} to {}, run qk --rollback to go back to v{CURRENT_VERSION}", response.tag_name, destination.display());
+
+    Ok(())
+}
+
+/// Where the new binary goes: the running executable, so it's replaced wherever it was installed, or
+/// `qk` in the given directory.
+///
+/// # Errors
+///
+/// Returns an error if the running executable can't be found.
+pub fn install_path(install_dir: Option<&Path>) -> Result<PathBuf> {
+    match install_dir {
+        Some(dir) => Ok(dir.join(BINARY_NAME)),
+        // Resolved through symlinks, so e.g. a Homebrew style link is left pointing at the new binary.
+        None => Ok(std::env::current_exe()?.canonicalize()?),
+    }
+}
+
+/// Where the binary replaced by an update is kept, so it can be rolled back to.
+pub fn backup_path(destination: &Path) -> PathBuf {
+    let mut backup = destination.as_os_str().to_owned();
+    backup.push(".previous");
+    PathBuf::from(backup)
+}
+
+/// Swaps the installed binary with the one it replaced, so rolling back again returns to the newer version.
+///
+/// # Arguments
+///
+/// * `install_dir` - The directory qk was installed into, instead of the running executable's.
+///
+/// # Errors
+///
+/// Returns `UpdateError::NoBackup` if nothing has been replaced by an update yet, or an error if the
+/// swap fails.
+pub fn rollback(install_dir: Option<&Path>) -> Result<()> {
+    let destination = install_path(install_dir)?;
+    let backup = backup_path(&destination);
+    let mut previous = File::open(&backup).map_err(|_| UpdateError::NoBackup(backup.display().to_string()))?;
+
+    // Read in full before the backup is replaced with the current binary.
+    install(&mut previous, &destination)?;
+    println!("Rolled back {}, run qk --rollback again to undo", destination.display());
+
+    Ok(())
+}
+
+/// Installs a binary, staging it next to the destination then renaming it into place, so the
+/// destination is replaced atomically and is never left half written. Anything already at the
+/// destination is kept as a backup, see `backup_path`.
+///
+/// # Arguments
+///
+/// * `binary` - The new binary's contents.
+/// * `destination` - Where to install it, replacing anything already there.
+///
+/// # Errors
+///
+/// Returns `UpdateError::InstallDirNotWritable` if the destination's directory is read only or owned by
+/// someone else, or an error if the binary can't be read or written.
+pub fn install(binary: &mut impl Read, destination: &Path) -> Result<()> {
+    let dir = destination.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let file_name = destination.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_else(|| BINARY_NAME.to_string());
+    let staged = dir.join(format!(".{file_name}.new"));
+    let not_writable = |err: io::Error| -> Error {
+        match err.kind() {
+            io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem => UpdateError::InstallDirNotWritable(dir.display().to_string()).into(),
+            _ => err.into(),
+        }
+    };
+
+    fs::create_dir_all(dir).map_err(not_writable)?;
+    let mut staged_file = File::create(&staged).map_err(not_writable)?;
+    let result = io::copy(binary, &mut staged_file)
+        .and_then(|_| staged_file.sync_all())
+        .map_err(Error::from)
+        .and_then(|_| make_executable(&staged, destination))
+        .and_then(|_| match destination.exists() {
+            true => fs::copy(destination, backup_path(destination)).map(|_| ()).map_err(not_writable),
+            false => Ok(()),
+        })
+        .and_then(|_| fs::rename(&staged, destination).map_err(not_writable));
+
+    if result.is_err() {
+        let _ = fs::remove_file(&staged);
+    }
+    result
+}
+
+/// Gives the staged binary the permissions of the one it replaces, or `rwxr-xr-x` for a fresh install.
+#[cfg(unix)]
+fn make_executable(staged: &Path, destination: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let permissions = fs::metadata(destination)
+        .map(|metadata| metadata.permissions())
+        .unwrap_or_else(|_| fs::Permissions::from_mode(0o755));
+    fs::set_permissions(staged, permissions)?;
     Ok(())
 }
 
+#[cfg(not(unix))]
+fn make_executable(_staged: &Path, _destination: &Path) -> Result<()> {
+    Ok(())
+}
 
+#[cfg(test)]
+mod tests;
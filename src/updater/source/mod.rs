@@ -0,0 +1,270 @@
+use std::fs::{self, File};
+use std::future::Future;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use color_eyre::eyre::{eyre, Result};
+use futures_util::StreamExt;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, USER_AGENT};
+use reqwest::{Client, Proxy, StatusCode};
+use serde::Deserialize;
+use tempfile::NamedTempFile;
+
+use crate::cli;
+use crate::settings::UpdateSettings;
+
+use super::UpdateError;
+
+/// Where qk's releases are published.
+pub const RELEASES_URL: &str = "https://api.github.com/repos/jamesgiu/quick-kit/releases";
+/// The file in a mirror holding the latest release's tag.
+const MIRROR_LATEST: &str = "LATEST";
+/// The checksum list in each of a mirror's releases, which also says what's in the release.
+const MIRROR_CHECKSUMS: &str = "SHA256SUMS";
+/// How long to wait to connect, or for the next chunk of a download, before the attempt fails.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// How many times to try a request that fails in a way that might not happen again.
+const ATTEMPTS: u32 = 3;
+/// How long to wait before the first retry, doubling each time.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+/// How wide the download progress bar is.
+const PROGRESS_BAR_WIDTH: u64 = 30;
+
+#[derive(Deserialize, Debug)]
+pub struct Asset {
+    pub name: String,
+    pub browser_download_url: String
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReleaseMeta {
+    pub tag_name: String,
+    /// The release notes.
+    pub body: Option<String>,
+    pub assets: Vec<Asset>
+}
+
+/// How requests that fail in a way that might not happen again are retried.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Retries {
+    /// How long to wait before the first retry, doubling each time.
+    pub delay: Duration,
+    /// Whether to retry without saying so, e.g. while the TUI has the terminal.
+    pub quiet: bool,
+}
+
+impl Default for Retries {
+    fn default() -> Self {
+        Retries { delay: RETRY_DELAY, quiet: false }
+    }
+}
+
+/// Where releases are fetched from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// The GitHub releases API at the given URL, or anything that serves the same JSON.
+    Api(String),
+    /// A plain directory of releases, at a URL or on disk, laid out as `LATEST` holding the latest tag and
+    /// a directory per tag holding the assets and a `SHA256SUMS` listing them.
+    Mirror(String),
+}
+
+impl Source {
+    /// The mirror if one is configured, otherwise the configured releases API, otherwise GitHub.
+    pub fn from_settings(settings: &UpdateSettings) -> Self {
+        match (&settings.mirror, &settings.releases_url) {
+            (Some(mirror), _) => Source::Mirror(mirror.trim_end_matches('/').to_string()),
+            (None, Some(releases_url)) => Source::Api(releases_url.trim_end_matches('/').to_string()),
+            (None, None) => Source::Api(RELEASES_URL.to_string()),
+        }
+    }
+
+    /// Fetches a release by its tag, or the latest one.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - The release to fetch, or `None` for the latest.
+    /// * `retries` - How to retry requests that fail in a way that might not happen again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UpdateError::ReleaseNotFound` if there's no release with the tag, or an error if the
+    /// source can't be reached or read.
+    pub async fn fetch_release(&self, client: &Client, tag: Option<&str>, retries: Retries) -> Result<ReleaseMeta> {
+        match self {
+            Source::Api(releases_url) => {
+                let url = match tag {
+                    Some(tag) => format!("{releases_url}/tags/{tag}"),
+                    None => format!("{releases_url}/latest"),
+                };
+                let response = with_retries(&url, retries, || send(client, &url)).await?;
+
+                if let (Some(tag), StatusCode::NOT_FOUND) = (tag, response.status()) {
+                    return Err(UpdateError::ReleaseNotFound(tag.to_string()).into());
+                }
+
+                Ok(response.error_for_status()?.json::<ReleaseMeta>().await?)
+            }
+            Source::Mirror(mirror) => {
+                let tag = match tag {
+                    Some(tag) => tag.to_string(),
+                    None => String::from_utf8_lossy(&download(client, &format!("{mirror}/{MIRROR_LATEST}"), retries).await?).trim().to_string(),
+                };
+                let checksums = download(client, &format!("{mirror}/{tag}/{MIRROR_CHECKSUMS}"), retries).await
+                    .map_err(|_| UpdateError::ReleaseNotFound(tag.clone()))?;
+
+                Ok(mirror_release(mirror, &tag, &String::from_utf8_lossy(&checksums)))
+            }
+        }
+    }
+}
+
+/// Describes a mirrored release from its checksum list, as the directory can't be listed. Every asset
+/// may have a signature, which is only fetched if signatures are being checked.
+fn mirror_release(mirror: &str, tag: &str, checksums: &str) -> ReleaseMeta {
+    let asset = |name: String| Asset { browser_download_url: format!("{mirror}/{tag}/{name}"), name };
+    let names = checksums.lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(|name| name.trim_start_matches('*').rsplit('/').next().unwrap_or(name).to_string());
+
+    let assets = names
+        .flat_map(|name| [format!("{name}.minisig"), name])
+        .chain(std::iter::once(MIRROR_CHECKSUMS.to_string()))
+        .map(asset)
+        .collect();
+
+    ReleaseMeta { tag_name: tag.to_string(), body: None, assets }
+}
+
+/// Builds the HTTP client for updates, going through the configured proxy and sending the configured token.
+/// Without a proxy configured, the usual `HTTPS_PROXY`, `HTTP_PROXY` and `NO_PROXY` variables apply.
+///
+/// # Errors
+///
+/// Returns an error if the proxy URL or token isn't valid.
+pub fn client(settings: &UpdateSettings) -> Result<Client> {
+    let mut headers = HeaderMap::new();
+    headers.insert(USER_AGENT, HeaderValue::from_static("quick-kit"));
+    if let Some(token) = &settings.token {
+        let mut authorization = HeaderValue::from_str(&format!("Bearer {token}")).map_err(|_| eyre!("Invalid update token"))?;
+        authorization.set_sensitive(true);
+        headers.insert(AUTHORIZATION, authorization);
+    }
+
+    let mut builder = Client::builder()
+        .default_headers(headers)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT);
+    if let Some(proxy) = &settings.proxy {
+        builder = builder.proxy(Proxy::all(proxy).map_err(|err| eyre!("Invalid update proxy {proxy}: {err}"))?);
+    }
+
+    Ok(builder.build()?)
+}
+
+/// The path to read from when a location is on disk rather than at a URL.
+fn local_path(location: &str) -> Option<&str> {
+    (!location.starts_with("http://") && !location.starts_with("https://")).then(|| location.trim_start_matches("file://"))
+}
+
+/// Sends a GET request, failing on server errors so they're retried. Other errors are left to the caller,
+/// e.g. a missing release.
+async fn send(client: &Client, url: &str) -> Result<reqwest::Response> {
+    let response = client.get(url).send().await?;
+    if response.status().is_server_error() {
+        response.error_for_status_ref()?;
+    }
+
+    Ok(response)
+}
+
+/// Whether a failed request might succeed if it's tried again.
+fn is_transient(err: &color_eyre::eyre::Error) -> bool {
+    err.downcast_ref::<reqwest::Error>().is_some_and(|err| {
+        err.is_timeout() || err.is_connect() || err.is_body() || err.status().is_some_and(|status| status.is_server_error())
+    })
+}
+
+/// Runs a request, trying again with a growing delay when it fails in a way that might not happen again.
+async fn with_retries<T, F, Fut>(what: &str, retries: Retries, mut request: F) -> Result<T>
+    where F: FnMut() -> Fut, Fut: Future<Output = Result<T>> {
+    let mut delay = retries.delay;
+    for _ in 1..ATTEMPTS {
+        match request().await {
+            Err(err) if is_transient(&err) => {
+                if !retries.quiet {
+                    eprintln!("Couldn't fetch {what}, retrying in {}s: {err}", delay.as_secs_f32());
+                }
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+
+    request().await
+}
+
+/// Downloads from a URL, or reads from disk for a mirror that's a plain directory. For small files like
+/// checksums, see `download_to_file` for the asset itself.
+///
+/// # Errors
+///
+/// Returns an error if the download fails after retrying or the file can't be read.
+pub async fn download(client: &Client, location: &str, retries: Retries) -> Result<Vec<u8>> {
+    if let Some(path) = local_path(location) {
+        return fs::read(path).map_err(|err| eyre!("Couldn't read {path}: {err}"));
+    }
+
+    with_retries(location, retries, || async {
+        let bytes = send(client, location).await?.error_for_status()?.bytes().await?;
+        Ok(bytes.to_vec())
+    }).await
+}
+
+/// Streams a download into a temp file only the current user can read, showing its progress. The file is
+/// deleted when it's dropped, so nothing is left behind if the update fails.
+///
+/// # Errors
+///
+/// Returns an error if the download fails after retrying or the file can't be read or written.
+pub async fn download_to_file(client: &Client, location: &str, retries: Retries) -> Result<NamedTempFile> {
+    if let Some(path) = local_path(location) {
+        let mut file = NamedTempFile::with_prefix("qk-update-")?;
+        io::copy(&mut File::open(path).map_err(|err| eyre!("Couldn't read {path}: {err}"))?, &mut file)?;
+        return Ok(file);
+    }
+
+    with_retries(location, retries, || async {
+        // A fresh file for each attempt, so a retry doesn't append to a partial download.
+        let mut file = NamedTempFile::with_prefix("qk-update-")?;
+        let response = send(client, location).await?.error_for_status()?;
+        let total = response.content_length();
+        let mut downloaded = 0;
+        let mut chunks = response.bytes_stream();
+        while let Some(chunk) = chunks.next().await {
+            let chunk = chunk?;
+            file.write_all(&chunk)?;
+            downloaded += chunk.len() as u64;
+            // Padded to clear what's left of a longer line before it.
+            eprint!("\r{:<80}", progress_bar(downloaded, total));
+        }
+        eprintln!();
+
+        file.flush()?;
+        Ok(file)
+    }).await
+}
+
+/// A progress bar for a download, with how much has been downloaded so far.
+fn progress_bar(downloaded: u64, total: Option<u64>) -> String {
+    let filled = match total {
+        Some(total) if total > 0 => (downloaded.min(total) * PROGRESS_BAR_WIDTH / total) as usize,
+        _ => 0,
+    };
+    format!("[{}{}] {}", "#".repeat(filled), "-".repeat(PROGRESS_BAR_WIDTH as usize - filled), cli::format_progress(downloaded, total))
+}
+
+#[cfg(test)]
+mod tests;
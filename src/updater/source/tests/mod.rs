@@ -0,0 +1,120 @@
+use std::io::{BufRead, BufReader, Read};
+use std::net::TcpListener;
+use std::path::PathBuf;
+
+use super::*;
+
+/// A mirror on disk with a single release, v99.0.0.
+fn test_mirror(name: &str) -> PathBuf {
+    let mirror = std::env::temp_dir().join(format!("qk-mirror-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&mirror);
+    fs::create_dir_all(mirror.join("v99.0.0")).unwrap();
+    fs::write(mirror.join("LATEST"), "v99.0.0\n").unwrap();
+    fs::write(mirror.join("v99.0.0/SHA256SUMS"), "aaaa  qk-x86_64-unknown-linux-gnu.zip\nbbbb *qk-aarch64-apple-darwin.zip\n").unwrap();
+    mirror
+}
+
+#[test]
+fn test_from_settings() {
+    assert_eq!(Source::Api(RELEASES_URL.to_string()), Source::from_settings(&UpdateSettings::default()));
+
+    let settings = UpdateSettings { releases_url: Some("https://ghe.internal/api/v3/repos/tools/qk/releases/".to_string()), ..Default::default() };
+    assert_eq!(Source::Api("https://ghe.internal/api/v3/repos/tools/qk/releases".to_string()), Source::from_settings(&settings));
+
+    let settings = UpdateSettings { mirror: Some("/srv/qk".to_string()), ..settings };
+    assert_eq!(Source::Mirror("/srv/qk".to_string()), Source::from_settings(&settings));
+}
+
+#[test]
+fn test_mirror_release() {
+    let release = mirror_release("https://mirror.internal/qk", "v99.0.0", "aaaa  qk-x86_64-unknown-linux-gnu.zip\n");
+
+    assert_eq!("v99.0.0", release.tag_name);
+    let assets: Vec<(&str, &str)> = release.assets.iter().map(|asset| (asset.name.as_str(), asset.browser_download_url.as_str())).collect();
+    assert_eq!(vec![
+        ("qk-x86_64-unknown-linux-gnu.zip.minisig", "https://mirror.internal/qk/v99.0.0/qk-x86_64-unknown-linux-gnu.zip.minisig"),
+        ("qk-x86_64-unknown-linux-gnu.zip", "https://mirror.internal/qk/v99.0.0/qk-x86_64-unknown-linux-gnu.zip"),
+        ("SHA256SUMS", "https://mirror.internal/qk/v99.0.0/SHA256SUMS"),
+    ], assets);
+}
+
+#[tokio::test]
+async fn test_fetch_release_from_mirror_on_disk() {
+    let mirror = test_mirror("latest");
+    let source = Source::Mirror(mirror.display().to_string());
+
+    let release = source.fetch_release(&Client::new(), None, Retries::default()).await.unwrap();
+
+    assert_eq!("v99.0.0", release.tag_name);
+    let names: Vec<&str> = release.assets.iter().map(|asset| asset.name.as_str()).collect();
+    assert!(names.contains(&"qk-x86_64-unknown-linux-gnu.zip"));
+    assert!(names.contains(&"qk-aarch64-apple-darwin.zip"));
+    fs::remove_dir_all(mirror).unwrap();
+}
+
+#[tokio::test]
+async fn test_fetch_release_from_mirror_unknown_tag() {
+    let mirror = test_mirror("unknown-tag");
+    let source = Source::Mirror(mirror.display().to_string());
+
+    let err = source.fetch_release(&Client::new(), Some("v0.0.1"), Retries::default()).await.unwrap_err();
+
+    assert_eq!("No release tagged v0.0.1", err.to_string());
+    fs::remove_dir_all(mirror).unwrap();
+}
+
+#[test]
+fn test_client_invalid_proxy() {
+    let settings = UpdateSettings { proxy: Some("not a proxy".to_string()), ..Default::default() };
+
+    assert!(client(&settings).unwrap_err().to_string().starts_with("Invalid update proxy not a proxy"));
+}
+
+/// Serves `served` for every request, after failing the first `failures` with a server error.
+fn serve_flaky(failures: usize, served: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/qk.zip", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        for (request, mut stream) in listener.incoming().flatten().enumerate() {
+            let mut header = String::new();
+            let mut reader = BufReader::new(&stream);
+            while reader.read_line(&mut header).is_ok_and(|read| read > 2) {
+                header.clear();
+            }
+
+            let (status, body) = if request < failures { ("503 Service Unavailable", "") } else { ("200 OK", served) };
+            let _ = write!(stream, "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len());
+        }
+    });
+    url
+}
+
+/// Retries without waiting long, so the tests don't.
+const QUICK_RETRIES: Retries = Retries { delay: Duration::from_millis(10), quiet: false };
+
+#[tokio::test]
+async fn test_download_to_file_retries() {
+    let url = serve_flaky(ATTEMPTS as usize - 1, "qk release");
+
+    let file = download_to_file(&Client::new(), &url, QUICK_RETRIES).await.unwrap();
+
+    let mut downloaded = String::new();
+    file.reopen().unwrap().read_to_string(&mut downloaded).unwrap();
+    assert_eq!("qk release", downloaded);
+}
+
+#[tokio::test]
+async fn test_download_to_file_gives_up() {
+    let url = serve_flaky(ATTEMPTS as usize, "qk release");
+
+    let err = download_to_file(&Client::new(), &url, QUICK_RETRIES).await.unwrap_err();
+
+    assert!(err.to_string().contains("503 Service Unavailable"));
+}
+
+#[test]
+fn test_progress_bar() {
+    assert_eq!("[#######-----------------------] 256 B of 1.0 KiB (25%)", progress_bar(256, Some(1024)));
+    assert_eq!("[------------------------------] 256 B", progress_bar(256, None));
+}
@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Cursor, Write};
+use std::net::TcpListener;
+
+use sha2::{Digest, Sha256};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use super::*;
+
+fn target(os: &str, arch: &str, libc: &str) -> Target {
+    Target { os: os.to_string(), arch: arch.to_string(), libc: libc.to_string() }
+}
+
+const ASSETS: [&str; 5] = [
+    "qk-x86_64-unknown-linux-musl.zip",
+    "qk-x86_64-unknown-linux-gnu.zip",
+    "qk-aarch64-unknown-linux-gnu.zip",
+    "qk-aarch64-apple-darwin.zip",
+    "qk-x86_64-pc-windows-msvc.zip",
+];
+
+#[test]
+fn test_triple() {
+    assert_eq!("x86_64-unknown-linux-gnu", target("linux", "x86_64", "gnu").triple());
+    assert_eq!("aarch64-apple-darwin", target("macos", "aarch64", "").triple());
+    assert_eq!("x86_64-pc-windows-msvc", target("windows", "x86_64", "").triple());
+}
+
+#[test]
+fn test_select_asset_exact_triple() {
+    assert_eq!(1, select_asset(&ASSETS, &target("linux", "x86_64", "gnu")).unwrap());
+    assert_eq!(0, select_asset(&ASSETS, &target("linux", "x86_64", "musl")).unwrap());
+    assert_eq!(2, select_asset(&ASSETS, &target("linux", "aarch64", "gnu")).unwrap());
+    assert_eq!(3, select_asset(&ASSETS, &target("macos", "aarch64", "")).unwrap());
+    assert_eq!(4, select_asset(&ASSETS, &target("windows", "x86_64", "")).unwrap());
+}
+
+#[test]
+fn test_select_asset_aliases() {
+    let assets = ["qk-darwin-arm64.tar.gz", "qk-linux-amd64.tar.gz"];
+
+    assert_eq!(0, select_asset(&assets, &target("macos", "aarch64", "")).unwrap());
+    assert_eq!(1, select_asset(&assets, &target("linux", "x86_64", "gnu")).unwrap());
+}
+
+#[test]
+fn test_select_asset_matches_whole_words() {
+    let assets = ["qk-aarch64-unknown-linux-gnu.zip", "qk_linux_arm64.tar.gz", "qk-x86_64-unknown-linux-gnu.zip"];
+
+    assert!(select_asset(&assets, &target("linux", "arm", "gnu")).is_err());
+    assert!(select_asset(&assets, &target("linux", "x86", "gnu")).is_err());
+
+    let assets = ["qk_linux_arm64.tar.gz", "qk_linux_armv7.tar.gz", "qk_linux_x86-64.tar.gz"];
+
+    assert_eq!(1, select_asset(&assets, &target("linux", "arm", "gnu")).unwrap());
+    assert_eq!(2, select_asset(&assets, &target("linux", "x86_64", "gnu")).unwrap());
+}
+
+#[test]
+fn test_select_asset_musl_fallback() {
+    let assets = ["qk-x86_64-unknown-linux-musl.zip"];
+
+    assert_eq!(0, select_asset(&assets, &target("linux", "x86_64", "gnu")).unwrap());
+}
+
+#[test]
+fn test_select_asset_no_match() {
+    let err = select_asset(&["qk-x86_64-unknown-linux-gnu.zip", "qk-aarch64-apple-darwin.zip"], &target("linux", "x86_64", "musl")).unwrap_err();
+
+    assert_eq!(
+        "No release asset found for x86_64-unknown-linux-musl, available assets: qk-x86_64-unknown-linux-gnu.zip, qk-aarch64-apple-darwin.zip",
+        err.to_string()
+    );
+}
+
+/// A fresh directory for a test to install into.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("qk-updater-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_install_path_with_install_dir() {
+    assert_eq!(Path::new("/opt/bin").join(BINARY_NAME), install_path(Some(Path::new("/opt/bin"))).unwrap());
+}
+
+#[test]
+fn test_install_fresh() {
+    let dir = test_dir("fresh");
+    let destination = dir.join("qk");
+
+    install(&mut "new binary".as_bytes(), &destination).unwrap();
+
+    assert_eq!("new binary", fs::read_to_string(&destination).unwrap());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(0o755, fs::metadata(&destination).unwrap().permissions().mode() & 0o777);
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_install_replaces_existing() {
+    let dir = test_dir("replace");
+    let destination = dir.join("qk");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(&destination, "old binary").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&destination, fs::Permissions::from_mode(0o700)).unwrap();
+    }
+
+    install(&mut "new binary".as_bytes(), &destination).unwrap();
+
+    assert_eq!("new binary", fs::read_to_string(&destination).unwrap());
+    assert!(!dir.join(".qk.new").exists());
+    assert_eq!("old binary", fs::read_to_string(dir.join("qk.previous")).unwrap());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(0o700, fs::metadata(&destination).unwrap().permissions().mode() & 0o777);
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_is_newer() {
+    assert!(is_newer("v0.2.0", "0.1.10").unwrap());
+    assert!(is_newer("0.1.11", "0.1.10").unwrap());
+    assert!(!is_newer("v0.1.10", "0.1.10").unwrap());
+    // Compared numerically, not as text.
+    assert!(!is_newer("v0.1.9", "0.1.10").unwrap());
+    assert!(!is_newer("v0.2.0-rc.1", "0.2.0").unwrap());
+}
+
+#[test]
+fn test_is_newer_invalid_tag() {
+    let err = is_newer("nightly", "0.1.10").unwrap_err();
+
+    assert_eq!("Release tag nightly isn't a version, expected e.g. v1.2.3", err.to_string());
+}
+
+/// A stand-in for the GitHub API and its downloads, serving fixed responses by path for the rest of the test run.
+fn serve(listener: TcpListener, routes: HashMap<String, Vec<u8>>) {
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(&stream);
+            let mut request_line = String::new();
+            let _ = reader.read_line(&mut request_line);
+            let mut header = String::new();
+            while reader.read_line(&mut header).is_ok_and(|read| read > 2) {
+                header.clear();
+            }
+
+            let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+            let (status, body) = match routes.get(path) {
+                Some(body) => ("200 OK", body.as_slice()),
+                None => ("404 Not Found", "Not Found".as_bytes()),
+            };
+            let _ = write!(stream, "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+            let _ = stream.write_all(body);
+        }
+    });
+}
+
+fn release_zip() -> Vec<u8> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("README.md", FileOptions::default()).unwrap();
+    zip.write_all(b"# qk").unwrap();
+    zip.start_file("qk-v99.0.0/qk", FileOptions::default()).unwrap();
+    zip.write_all(b"new binary").unwrap();
+    zip.finish().unwrap().into_inner()
+}
+
+/// Serves a release newer than this one, returning the URL of its releases.
+///
+/// # Arguments
+///
+/// * `served_asset` - What's downloaded for the asset, which may not be what was checksummed.
+/// * `with_checksum` - Whether the release publishes a checksum for the asset.
+fn serve_release(served_asset: Vec<u8>, with_checksum: bool) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let asset = format!("qk-{}.zip", Target::current().triple());
+    let checksum = format!("{:x}  {asset}\n", Sha256::digest(release_zip()));
+
+    let mut assets = vec![serde_json::json!({ "name": asset, "browser_download_url": format!("{url}/download/{asset}") })];
+    if with_checksum {
+        assets.push(serde_json::json!({ "name": format!("{asset}.sha256"), "browser_download_url": format!("{url}/download/{asset}.sha256") }));
+    }
+    let release = serde_json::json!({ "tag_name": "v99.0.0", "body": "Faster matching", "assets": assets });
+
+    serve(listener, HashMap::from([
+        ("/releases/latest".to_string(), release.to_string().into_bytes()),
+        ("/releases/tags/v99.0.0".to_string(), release.to_string().into_bytes()),
+        (format!("/download/{asset}"), served_asset),
+        (format!("/download/{asset}.sha256"), checksum.into_bytes()),
+    ]));
+    format!("{url}/releases")
+}
+
+#[tokio::test]
+async fn test_update_from_installs_verified_release() {
+    let dir = test_dir("verified");
+    let releases_url = serve_release(release_zip(), true);
+
+    update_from(&Client::new(), &Source::Api(releases_url), None, Some(&dir), None).await.unwrap();
+
+    assert_eq!("new binary", fs::read_to_string(dir.join(BINARY_NAME)).unwrap());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_update_from_refuses_checksum_mismatch() {
+    let dir = test_dir("mismatch");
+    let releases_url = serve_release(b"tampered".to_vec(), true);
+
+    let err = update_from(&Client::new(), &Source::Api(releases_url), None, Some(&dir), None).await.unwrap_err();
+
+    assert!(matches!(err.downcast_ref::<UpdateError>(), Some(UpdateError::ChecksumMismatch(..))));
+    assert!(!dir.join(BINARY_NAME).exists());
+}
+
+#[tokio::test]
+async fn test_update_from_refuses_missing_checksum() {
+    let dir = test_dir("unchecked");
+    let releases_url = serve_release(release_zip(), false);
+
+    let err = update_from(&Client::new(), &Source::Api(releases_url), None, Some(&dir), None).await.unwrap_err();
+
+    assert!(matches!(err.downcast_ref::<UpdateError>(), Some(UpdateError::ChecksumMissing(..))));
+    assert!(!dir.join(BINARY_NAME).exists());
+}
+
+#[tokio::test]
+async fn test_update_from_refuses_missing_signature() {
+    let dir = test_dir("unsigned");
+    let releases_url = serve_release(release_zip(), true);
+
+    let err = update_from(&Client::new(), &Source::Api(releases_url), Some("RWQBAgMEBQYHCAOhB7/zzhC+HXDdGOdLwJln5NYwm6UNXx3chmQSVTG4"), Some(&dir), None).await.unwrap_err();
+
+    assert!(matches!(err.downcast_ref::<UpdateError>(), Some(UpdateError::InvalidSignature(..))));
+    assert!(!dir.join(BINARY_NAME).exists());
+}
+
+#[tokio::test]
+async fn test_update_from_installs_tagged_release() {
+    let dir = test_dir("tagged");
+    let releases_url = serve_release(release_zip(), true);
+
+    update_from(&Client::new(), &Source::Api(releases_url), None, Some(&dir), Some("v99.0.0")).await.unwrap();
+
+    assert_eq!("new binary", fs::read_to_string(dir.join(BINARY_NAME)).unwrap());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_update_from_unknown_tag() {
+    let dir = test_dir("unknown-tag");
+    let releases_url = serve_release(release_zip(), true);
+
+    let err = update_from(&Client::new(), &Source::Api(releases_url), None, Some(&dir), Some("v0.0.1")).await.unwrap_err();
+
+    assert_eq!("No release tagged v0.0.1", err.to_string());
+}
+
+#[test]
+fn test_rollback_swaps_with_backup() {
+    let dir = test_dir("rollback");
+    let destination = dir.join(BINARY_NAME);
+    install(&mut "old binary".as_bytes(), &destination).unwrap();
+    install(&mut "new binary".as_bytes(), &destination).unwrap();
+
+    rollback(Some(&dir)).unwrap();
+
+    assert_eq!("old binary", fs::read_to_string(&destination).unwrap());
+    assert_eq!("new binary", fs::read_to_string(backup_path(&destination)).unwrap());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_rollback_without_backup() {
+    let dir = test_dir("no-backup");
+
+    let err = rollback(Some(&dir)).unwrap_err();
+
+    assert_eq!(format!("No previous version to roll back to at {}", backup_path(&dir.join(BINARY_NAME)).display()), err.to_string());
+}
+
+#[tokio::test]
+async fn test_update_from_mirror_on_disk() {
+    let dir = test_dir("mirrored");
+    let mirror = test_dir("mirror");
+    let asset = format!("qk-{}.zip", Target::current().triple());
+    fs::create_dir_all(mirror.join("v99.0.0")).unwrap();
+    fs::write(mirror.join("LATEST"), "v99.0.0").unwrap();
+    fs::write(mirror.join("v99.0.0").join(&asset), release_zip()).unwrap();
+    fs::write(mirror.join("v99.0.0/SHA256SUMS"), format!("{:x}  {asset}\n", Sha256::digest(release_zip()))).unwrap();
+
+    update_from(&Client::new(), &Source::Mirror(mirror.display().to_string()), None, Some(&dir), None).await.unwrap();
+
+    assert_eq!("new binary", fs::read_to_string(dir.join(BINARY_NAME)).unwrap());
+    fs::remove_dir_all(dir).unwrap();
+    fs::remove_dir_all(mirror).unwrap();
+}
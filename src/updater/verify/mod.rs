@@ -0,0 +1,107 @@
+use std::io::{self, Read};
+
+use color_eyre::eyre::Result;
+use minisign_verify::{PublicKey, Signature};
+use sha2::{Digest, Sha256};
+
+use super::UpdateError;
+
+/// Names of checksum files covering every asset in a release, as published by common release tooling.
+const CHECKSUM_LISTS: [&str; 4] = ["sha256sums", "sha256sums.txt", "checksums.txt", "checksums.sha256"];
+
+/// Whether an asset is a checksum or signature for another asset, rather than something to install.
+pub fn is_verification_file(name: &str) -> bool {
+    let name = name.to_lowercase();
+    CHECKSUM_LISTS.contains(&name.as_str())
+        || [".sha256", ".sha256sum", ".minisig", ".sig", ".asc"].iter().any(|extension| name.ends_with(extension))
+}
+
+/// Finds the checksum file for an asset: its own `<asset>.sha256`, or failing that a list covering the
+/// whole release.
+///
+/// # Arguments
+///
+/// * `asset_name` - The asset to be checked.
+/// * `assets` - The names of every asset in the release.
+///
+/// # Returns
+///
+/// The index of the checksum file, if the release has one.
+pub fn checksum_asset(asset_name: &str, assets: &[&str]) -> Option<usize> {
+    let own = [format!("{asset_name}.sha256"), format!("{asset_name}.sha256sum")];
+    assets.iter().position(|name| own.contains(&name.to_string()))
+        .or_else(|| assets.iter().position(|name| CHECKSUM_LISTS.contains(&name.to_lowercase().as_str())))
+}
+
+/// Finds an asset's checksum in a checksum file, either `sha256sum` output (`<hash>  <name>` per line) or
+/// just the hash on its own.
+fn expected_checksum(checksums: &str, asset_name: &str) -> Option<String> {
+    checksums.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            // `*` marks a binary mode checksum, and some tools include the path the asset was built at.
+            let name = parts.next().map(|name| name.trim_start_matches('*').rsplit('/').next().unwrap_or(name));
+            (name.is_none() || name == Some(asset_name)).then(|| hash.to_lowercase())
+        })
+        .next()
+}
+
+/// Checks a downloaded asset against its published SHA-256 checksum.
+///
+/// # Arguments
+///
+/// * `asset` - The downloaded asset.
+/// * `checksums` - The contents of the checksum file.
+/// * `asset_name` - The asset's name, to find its line in a list of checksums.
+///
+/// # Errors
+///
+/// Returns `UpdateError::ChecksumMissing` if the file has no checksum for the asset, or
+/// `UpdateError::ChecksumMismatch` if the asset doesn't match it.
+pub fn verify_checksum(asset: &mut impl Read, checksums: &str, asset_name: &str) -> Result<()> {
+    let expected = expected_checksum(checksums, asset_name).ok_or_else(|| UpdateError::ChecksumMissing(asset_name.to_string()))?;
+    let mut hasher = Sha256::new();
+    io::copy(asset, &mut hasher)?;
+    let actual = format!("{:x}", hasher.finalize());
+
+    if actual != expected {
+        return Err(UpdateError::ChecksumMismatch(asset_name.to_string(), expected, actual).into());
+    }
+    Ok(())
+}
+
+/// Checks a downloaded asset against its minisign signature. Only prehashed signatures, minisign's default,
+/// are accepted, as the asset is read in chunks.
+///
+/// # Arguments
+///
+/// * `asset` - The downloaded asset.
+/// * `signature` - The contents of the asset's `.minisig` file.
+/// * `public_key` - The base64 public key releases are signed with.
+/// * `asset_name` - The asset's name, for the error.
+///
+/// # Errors
+///
+/// Returns `UpdateError::InvalidSignature` if the signature or key can't be read, or the asset wasn't
+/// signed with the key.
+pub fn verify_signature(asset: &mut impl Read, signature: &str, public_key: &str, asset_name: &str) -> Result<()> {
+    let invalid = |err: minisign_verify::Error| UpdateError::InvalidSignature(asset_name.to_string(), err.to_string());
+
+    let public_key = PublicKey::from_base64(public_key.trim()).map_err(invalid)?;
+    let signature = Signature::decode(signature).map_err(invalid)?;
+    let mut verifier = public_key.verify_stream(&signature).map_err(invalid)?;
+
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        match asset.read(&mut buffer)? {
+            0 => break,
+            read => verifier.update(&buffer[..read]),
+        }
+    }
+    verifier.finalize().map_err(invalid)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
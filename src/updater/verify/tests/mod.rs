@@ -0,0 +1,66 @@
+use super::*;
+
+const ASSET: &str = "qk-x86_64-unknown-linux-gnu.zip";
+/// The SHA-256 of `qk release`.
+const CHECKSUM: &str = "3f50e36c53ba0e3ba9e5f4c555b40f4802d550a424a2fc91c7fb9148d8c02e03";
+const PUBLIC_KEY: &str = "RWQBAgMEBQYHCAOhB7/zzhC+HXDdGOdLwJln5NYwm6UNXx3chmQSVTG4";
+const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQBAgMEBQYHCHEd+qqdFEaLAR1N2qO/m7hUmgndwH6eVod0O7b8C5vhhlAi2ARJBYwb7R9ySFO3UAVNULGNY0yX/JDBlNvPbQI=
+trusted comment: timestamp:1760000000\tfile:qk-x86_64-unknown-linux-gnu.zip\thashed
+9oMN8zJSGBH2nFaaZkuxzTTbkkGyhknf5zzxttn5Oj2pF2/XQmAL5tiDQj8KunDaR5JwX+17qE/u0jvGtofLAg==
+";
+
+#[test]
+fn test_is_verification_file() {
+    assert!(is_verification_file("qk-x86_64-unknown-linux-gnu.zip.sha256"));
+    assert!(is_verification_file("qk-x86_64-unknown-linux-gnu.zip.minisig"));
+    assert!(is_verification_file("SHA256SUMS"));
+    assert!(!is_verification_file(ASSET));
+}
+
+#[test]
+fn test_checksum_asset_prefers_own_file() {
+    let assets = ["SHA256SUMS", ASSET, "qk-x86_64-unknown-linux-gnu.zip.sha256"];
+
+    assert_eq!(Some(2), checksum_asset(ASSET, &assets));
+    assert_eq!(Some(0), checksum_asset(ASSET, &assets[..2]));
+    assert_eq!(None, checksum_asset(ASSET, &[ASSET]));
+}
+
+#[test]
+fn test_verify_checksum_list() {
+    let checksums = format!("0000  qk-aarch64-apple-darwin.zip\n{CHECKSUM} *dist/{ASSET}\n");
+
+    verify_checksum(&mut "qk release".as_bytes(), &checksums, ASSET).unwrap();
+}
+
+#[test]
+fn test_verify_checksum_hash_only() {
+    verify_checksum(&mut "qk release".as_bytes(), &CHECKSUM.to_uppercase(), ASSET).unwrap();
+}
+
+#[test]
+fn test_verify_checksum_mismatch() {
+    let err = verify_checksum(&mut "tampered".as_bytes(), CHECKSUM, ASSET).unwrap_err();
+
+    assert!(err.to_string().starts_with(&format!("Checksum mismatch for {ASSET}, expected {CHECKSUM} but downloaded ")));
+}
+
+#[test]
+fn test_verify_checksum_missing() {
+    let err = verify_checksum(&mut "qk release".as_bytes(), "0000  qk-aarch64-apple-darwin.zip", ASSET).unwrap_err();
+
+    assert_eq!(format!("No published checksum for {ASSET}, refusing to install it"), err.to_string());
+}
+
+#[test]
+fn test_verify_signature() {
+    verify_signature(&mut "qk release".as_bytes(), SIGNATURE, PUBLIC_KEY, ASSET).unwrap();
+}
+
+#[test]
+fn test_verify_signature_tampered() {
+    let err = verify_signature(&mut "tampered".as_bytes(), SIGNATURE, PUBLIC_KEY, ASSET).unwrap_err();
+
+    assert!(err.to_string().starts_with(&format!("Invalid signature for {ASSET}")));
+}